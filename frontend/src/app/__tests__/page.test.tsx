@@ -35,7 +35,7 @@ describe('Home Page', () => {
       value: '50000000000000000',
       source: 'Binance',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     });
     getHistory.mockResolvedValue([]);
 
@@ -78,7 +78,7 @@ export default function Home() {
       const hash = await submitOracleValue(
         priceData.price,
         priceData.source,
-        'success',
+        priceData.failover ? { Failover: priceData.failover } : 'Primary',
         { address: accounts[0].address, signer }
       );
       
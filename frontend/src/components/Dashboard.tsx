@@ -22,12 +22,15 @@ export default function Dashboard({ lastValue }: DashboardProps) {
   const updatedAt = new Date(parseInt(lastValue.updated_at) * 1000);
 
   const getStatusColor = (status: string) => {
-    switch (status.toLowerCase()) {
-      case 'success':
+    if (status.startsWith('Failover')) {
+      return 'bg-yellow-100 text-yellow-800';
+    }
+    switch (status) {
+      case 'Primary':
         return 'bg-green-100 text-green-800';
-      case 'warning':
+      case 'Degraded':
         return 'bg-yellow-100 text-yellow-800';
-      case 'error':
+      case 'Stale':
         return 'bg-red-100 text-red-800';
       default:
         return 'bg-gray-100 text-gray-800';
@@ -15,14 +15,14 @@ describe('Dashboard Component', () => {
       value: '50000000000000000', // 50000 * 1e12
       source: 'Binance',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     };
 
     render(<Dashboard lastValue={mockValue} />);
 
     expect(screen.getByText('$50,000.00')).toBeInTheDocument();
     expect(screen.getByText('Binance')).toBeInTheDocument();
-    expect(screen.getByText('success')).toBeInTheDocument();
+    expect(screen.getByText('Primary')).toBeInTheDocument();
   });
 
   it('should format price with 2 decimal places', () => {
@@ -30,7 +30,7 @@ describe('Dashboard Component', () => {
       value: '50000123456789000', // 50000.123456789 * 1e12
       source: 'Coinbase',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     };
 
     render(<Dashboard lastValue={mockValue} />);
@@ -43,7 +43,7 @@ describe('Dashboard Component', () => {
       value: '50000000000000000',
       source: 'Binance',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     };
 
     const { container } = render(<Dashboard lastValue={mockValue} />);
@@ -58,7 +58,7 @@ describe('Dashboard Component', () => {
       value: '50000000000000000',
       source: 'Coinbase',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     };
 
     const { container } = render(<Dashboard lastValue={mockValue} />);
@@ -68,19 +68,19 @@ describe('Dashboard Component', () => {
     expect(sourceBadge).toHaveTextContent('Coinbase');
   });
 
-  it('should display correct status badge color for success', () => {
+  it('should display correct status badge color for primary', () => {
     const mockValue: OracleValue = {
       value: '50000000000000000',
       source: 'Binance',
       updated_at: '1000',
-      status: 'success',
+      status: 'Primary',
     };
 
     const { container } = render(<Dashboard lastValue={mockValue} />);
     const statusBadge = container.querySelector('.bg-green-100');
     
     expect(statusBadge).toBeInTheDocument();
-    expect(statusBadge).toHaveTextContent('success');
+    expect(statusBadge).toHaveTextContent('Primary');
   });
 
   it('should display formatted timestamp', () => {
@@ -88,7 +88,7 @@ describe('Dashboard Component', () => {
       value: '50000000000000000',
       source: 'Binance',
       updated_at: '1000000000', // Unix timestamp
-      status: 'success',
+      status: 'Primary',
     };
 
     render(<Dashboard lastValue={mockValue} />);
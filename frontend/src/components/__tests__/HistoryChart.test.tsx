@@ -16,13 +16,13 @@ describe('HistoryChart Component', () => {
         value: '50000000000000000',
         source: 'Binance',
         updated_at: '1000',
-        status: 'success',
+        status: 'Primary',
       },
       {
         value: '51000000000000000',
         source: 'Coinbase',
         updated_at: '2000',
-        status: 'success',
+        status: 'Primary',
       },
     ];
 
@@ -40,7 +40,7 @@ describe('HistoryChart Component', () => {
         value: '50000000000000000', // 50000 * 1e12
         source: 'Binance',
         updated_at: '1000',
-        status: 'success',
+        status: 'Primary',
       },
     ];
 
@@ -1,18 +1,18 @@
-import { getLastValue, getHistory, submitOracleValue } from '../pallet';
-import { getApi } from '../api';
+import { getLastValue, getHistory, submitOracleValue, formatStatus } from '../pallet';
+import { getApi, sendRpc } from '../api';
 
 // Mock the API module
 jest.mock('../api', () => ({
   getApi: jest.fn(),
+  sendRpc: jest.fn(),
 }));
 
 describe('Pallet Interactions', () => {
   const mockApi = {
     query: {
       roracle: {
-        lastValue: jest.fn(),
-        history: jest.fn(),
         historyIndex: jest.fn(),
+        feedConfigs: jest.fn(),
       },
     },
     tx: {
@@ -22,35 +22,47 @@ describe('Pallet Interactions', () => {
     },
   };
 
+  const rpcValue = (value: string, source: string, updatedAt: number, status: any) => ({
+    value,
+    decimals: 12,
+    price: (Number(value) / 1e12).toString(),
+    source,
+    updatedAt,
+    observedAt: updatedAt,
+    status,
+    confidence: '0',
+  });
+
   beforeEach(() => {
     jest.clearAllMocks();
     (getApi as jest.Mock).mockResolvedValue(mockApi);
+    mockApi.query.roracle.feedConfigs.mockResolvedValue({
+      isNone: false,
+      unwrap: () => ({ decimals: { toNumber: () => 12 } }),
+    });
+  });
+
+  describe('formatStatus', () => {
+    it('should format unit and failover statuses', () => {
+      expect(formatStatus('Primary')).toBe('Primary');
+      expect(formatStatus({ Failover: 2 })).toBe('Failover(2)');
+    });
   });
 
   describe('getLastValue', () => {
     it('should return null when no value exists', async () => {
-      mockApi.query.roracle.lastValue.mockResolvedValue({
-        isNone: true,
-      });
+      (sendRpc as jest.Mock).mockResolvedValue(null);
 
       const result = await getLastValue();
 
       expect(result).toBeNull();
-      expect(mockApi.query.roracle.lastValue).toHaveBeenCalled();
+      expect(sendRpc).toHaveBeenCalledWith('roracle_getLatest', [0]);
     });
 
     it('should return formatted oracle value', async () => {
-      const mockValue = {
-        isNone: false,
-        unwrap: () => ({
-          value: { toString: () => '50000000000000000' }, // 50000 * 1e12
-          source: new Uint8Array([66, 105, 110, 97, 110, 99, 101]), // "Binance"
-          updated_at: { toString: () => '1000' },
-          status: new Uint8Array([115, 117, 99, 99, 101, 115, 115]), // "success"
-        }),
-      };
-
-      mockApi.query.roracle.lastValue.mockResolvedValue(mockValue);
+      (sendRpc as jest.Mock).mockResolvedValue(
+        rpcValue('50000000000000000', 'Binance', 1000, 'Primary') // 50000 * 1e12
+      );
 
       const result = await getLastValue();
 
@@ -58,9 +70,17 @@ describe('Pallet Interactions', () => {
         value: '50000000000000000',
         source: 'Binance',
         updated_at: '1000',
-        status: 'success',
+        status: 'Primary',
       });
     });
+
+    it('should query the given feed', async () => {
+      (sendRpc as jest.Mock).mockResolvedValue(null);
+
+      await getLastValue(3);
+
+      expect(sendRpc).toHaveBeenCalledWith('roracle_getLatest', [3]);
+    });
   });
 
   describe('getHistory', () => {
@@ -68,6 +88,7 @@ describe('Pallet Interactions', () => {
       mockApi.query.roracle.historyIndex.mockResolvedValue({
         toNumber: () => 0,
       });
+      (sendRpc as jest.Mock).mockResolvedValue([]);
 
       const result = await getHistory();
 
@@ -78,31 +99,16 @@ describe('Pallet Interactions', () => {
       mockApi.query.roracle.historyIndex.mockResolvedValue({
         toNumber: () => 2,
       });
-
-      mockApi.query.roracle.history
-        .mockResolvedValueOnce({
-          isSome: true,
-          unwrap: () => ({
-            value: { toString: () => '50000000000000000' },
-            source: new Uint8Array([66, 105, 110, 97, 110, 99, 101]),
-            updated_at: { toString: () => '1000' },
-            status: new Uint8Array([115, 117, 99, 99, 101, 115, 115]),
-          }),
-        })
-        .mockResolvedValueOnce({
-          isSome: true,
-          unwrap: () => ({
-            value: { toString: () => '51000000000000000' },
-            source: new Uint8Array([67, 111, 105, 110, 98, 97, 115, 101]),
-            updated_at: { toString: () => '2000' },
-            status: new Uint8Array([115, 117, 99, 99, 101, 115, 115]),
-          }),
-        });
+      (sendRpc as jest.Mock).mockResolvedValue([
+        { index: 0, ...rpcValue('50000000000000000', 'Binance', 1000, 'Primary') },
+        { index: 1, ...rpcValue('51000000000000000', 'Coinbase', 2000, { Failover: 1 }) },
+      ]);
 
       const result = await getHistory(10);
 
       expect(result).toHaveLength(2);
       expect(result[0].source).toBe('Coinbase'); // Most recent first (reversed)
+      expect(result[0].status).toBe('Failover(1)');
       expect(result[1].source).toBe('Binance');
     });
 
@@ -110,21 +116,13 @@ describe('Pallet Interactions', () => {
       mockApi.query.roracle.historyIndex.mockResolvedValue({
         toNumber: () => 10,
       });
-
-      mockApi.query.roracle.history.mockResolvedValue({
-        isSome: true,
-        unwrap: () => ({
-          value: { toString: () => '50000000000000000' },
-          source: new Uint8Array([66, 105, 110, 97, 110, 99, 101]),
-          updated_at: { toString: () => '1000' },
-          status: new Uint8Array([115, 117, 99, 99, 101, 115, 115]),
-        }),
-      });
+      (sendRpc as jest.Mock).mockResolvedValue([]);
 
       await getHistory(5);
 
       // Should only query last 5 entries
-      expect(mockApi.query.roracle.history).toHaveBeenCalledTimes(5);
+      expect(mockApi.query.roracle.historyIndex).toHaveBeenCalledWith(0);
+      expect(sendRpc).toHaveBeenCalledWith('roracle_getHistory', [0, 5, 10]);
     });
   });
 
@@ -140,17 +138,19 @@ describe('Pallet Interactions', () => {
       mockApi.tx.roracle.submitOracleValue.mockReturnValue(mockTx);
 
       const signer = { address: '5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY' };
-      const result = await submitOracleValue(50000, 'Binance', 'success', signer);
+      const result = await submitOracleValue(50000, 'Binance', 'Primary', signer);
 
       expect(result).toBe('0x123');
       expect(mockApi.tx.roracle.submitOracleValue).toHaveBeenCalledWith(
+        0,
         expect.any(BigInt),
         expect.any(Uint8Array),
-        expect.any(Uint8Array)
+        'Primary',
+        null
       );
     });
 
-    it('should convert price to u128 with 12 decimals', async () => {
+    it('should convert price to u128 with the decimals of the feed', async () => {
       const mockTx = {
         signAndSend: jest.fn((signer, callback) => {
           callback({ status: { isFinalized: true, asFinalized: '0x123' } });
@@ -161,13 +161,25 @@ describe('Pallet Interactions', () => {
       mockApi.tx.roracle.submitOracleValue.mockReturnValue(mockTx);
 
       const signer = { address: '5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY' };
-      await submitOracleValue(50000.50, 'Binance', 'success', signer);
+      await submitOracleValue(50000.50, 'Binance', { Failover: 1 }, signer);
 
       const callArgs = mockApi.tx.roracle.submitOracleValue.mock.calls[0];
-      const value = callArgs[0] as BigInt;
-      
+      const value = callArgs[1] as BigInt;
+
       // 50000.50 * 1e12 = 50000500000000000
       expect(value.toString()).toBe('50000500000000000');
+      expect(callArgs[3]).toEqual({ Failover: 1 });
+    });
+
+    it('should reject unregistered feeds', async () => {
+      mockApi.query.roracle.feedConfigs.mockResolvedValue({ isNone: true });
+
+      const signer = { address: '5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY' };
+
+      await expect(
+        submitOracleValue(50000, 'Binance', 'Primary', signer, 7)
+      ).rejects.toThrow('Feed 7 is not registered');
+      expect(mockApi.tx.roracle.submitOracleValue).not.toHaveBeenCalled();
     });
 
     it('should handle transaction errors', async () => {
@@ -180,9 +192,8 @@ describe('Pallet Interactions', () => {
       const signer = { address: '5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY' };
 
       await expect(
-        submitOracleValue(50000, 'Binance', 'success', signer)
+        submitOracleValue(50000, 'Binance', 'Primary', signer)
       ).rejects.toThrow('Transaction failed');
     });
   });
 });
-
@@ -73,6 +73,17 @@ export async function getApi(): Promise<ApiPromise> {
   }
 }
 
+/**
+ * Send a raw RPC request, e.g. to the `roracle_*` methods of the node
+ */
+export async function sendRpc<T>(method: string, params: unknown[]): Promise<T> {
+  await getApi();
+  if (!currentProvider) {
+    throw new Error('Not connected to an RPC endpoint');
+  }
+  return currentProvider.send<T>(method, params);
+}
+
 /**
  * Disconnect API
  */
@@ -4,6 +4,8 @@ export interface OraclePrice {
   price: number;
   source: string;
   timestamp: number;
+  /** Number of sources that failed before this one, set by `fetchOraclePrice` */
+  failover?: number;
 }
 
 const BINANCE_API = 'https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT';
@@ -65,11 +67,11 @@ export async function fetchOraclePrice(): Promise<OraclePrice> {
     { name: 'Kraken', fetch: fetchKrakenPrice },
   ];
 
-  for (const source of sources) {
+  for (const [failover, source] of sources.entries()) {
     try {
       const price = await source.fetch();
       console.log(`Successfully fetched price from ${source.name}:`, price.price);
-      return price;
+      return { ...price, failover };
     } catch (error) {
       console.warn(`${source.name} failed, trying next source...`, error);
       // Continue to next source
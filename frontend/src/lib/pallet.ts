@@ -1,5 +1,9 @@
-import { ApiPromise } from '@polkadot/api';
-import { getApi } from './api';
+import { getApi, sendRpc } from './api';
+
+/**
+ * Feed shown by the dashboard (BTC/USD in the development chain specs)
+ */
+export const DEFAULT_FEED = 0;
 
 export interface OracleValue {
   value: string;
@@ -9,30 +13,55 @@ export interface OracleValue {
 }
 
 /**
- * Get last oracle value
+ * Status of a value as serialized by the node, e.g. `"Primary"` or `{ "Failover": 1 }`
  */
-export async function getLastValue(): Promise<OracleValue | null> {
-  try {
-    const api = await getApi();
-    
-    // Check if roracle pallet exists
-    if (!api.query.roracle || !api.query.roracle.lastValue) {
-      throw new Error('Roracle pallet not found. Make sure you are connected to the R-Oracle chain.');
-    }
-    
-    const result = await api.query.roracle.lastValue();
-    
-    if (result.isNone) {
-      return null;
-    }
+export type OracleStatus = string | { [variant: string]: number };
+
+/**
+ * Oracle value as returned by the `roracle_*` RPC methods
+ */
+interface RpcOracleValue {
+  value: string;
+  decimals: number;
+  price: string;
+  source: string;
+  updatedAt: number;
+  observedAt: number;
+  status: OracleStatus;
+  confidence: string;
+}
+
+interface RpcHistoryEntry extends RpcOracleValue {
+  index: number;
+}
+
+/**
+ * Format a status for display, e.g. `Failover(1)`
+ */
+export function formatStatus(status: OracleStatus): string {
+  if (typeof status === 'string') {
+    return status;
+  }
+  const [variant, level] = Object.entries(status)[0];
+  return `${variant}(${level})`;
+}
+
+function toOracleValue(value: RpcOracleValue): OracleValue {
+  return {
+    value: value.value,
+    source: value.source,
+    updated_at: value.updatedAt.toString(),
+    status: formatStatus(value.status),
+  };
+}
 
-    const value = result.unwrap();
-    return {
-      value: value.value.toString(),
-      source: new TextDecoder().decode(value.source),
-      updated_at: value.updated_at.toString(),
-      status: new TextDecoder().decode(value.status),
-    };
+/**
+ * Get last oracle value of a feed
+ */
+export async function getLastValue(feed: number = DEFAULT_FEED): Promise<OracleValue | null> {
+  try {
+    const result = await sendRpc<RpcOracleValue | null>('roracle_getLatest', [feed]);
+    return result ? toOracleValue(result) : null;
   } catch (error: any) {
     console.error('Error getting last value:', error);
     throw new Error(`Failed to get last value: ${error.message}`);
@@ -40,37 +69,31 @@ export async function getLastValue(): Promise<OracleValue | null> {
 }
 
 /**
- * Get oracle history
+ * Get oracle history of a feed
  */
-export async function getHistory(limit: number = 10): Promise<OracleValue[]> {
+export async function getHistory(
+  limit: number = 10,
+  feed: number = DEFAULT_FEED
+): Promise<OracleValue[]> {
   try {
     const api = await getApi();
-    
+
     // Check if roracle pallet exists
     if (!api.query.roracle || !api.query.roracle.historyIndex) {
       throw new Error('Roracle pallet not found. Make sure you are connected to the R-Oracle chain.');
     }
-    
-    const index = await api.query.roracle.historyIndex();
+
+    const index = await api.query.roracle.historyIndex(feed);
     const currentIndex = index.toNumber();
-  
-    const history: OracleValue[] = [];
     const startIndex = Math.max(0, currentIndex - limit);
-  
-    for (let i = startIndex; i < currentIndex; i++) {
-      const result = await api.query.roracle.history(i);
-      if (result.isSome) {
-        const value = result.unwrap();
-        history.push({
-          value: value.value.toString(),
-          source: new TextDecoder().decode(value.source),
-          updated_at: value.updated_at.toString(),
-          status: new TextDecoder().decode(value.status),
-        });
-      }
-    }
-  
-    return history.reverse(); // Most recent first
+
+    const history = await sendRpc<RpcHistoryEntry[]>('roracle_getHistory', [
+      feed,
+      startIndex,
+      currentIndex,
+    ]);
+
+    return history.map(toOracleValue).reverse(); // Most recent first
   } catch (error: any) {
     console.error('Error getting history:', error);
     throw new Error(`Failed to get history: ${error.message}`);
@@ -78,28 +101,34 @@ export async function getHistory(limit: number = 10): Promise<OracleValue[]> {
 }
 
 /**
- * Submit oracle value
+ * Submit oracle value to a feed, observed at the time of the block it is included in
  */
 export async function submitOracleValue(
   value: number,
   source: string,
-  status: string,
-  signer: any
+  status: OracleStatus,
+  signer: any,
+  feed: number = DEFAULT_FEED
 ): Promise<string> {
   try {
     const api = await getApi();
-    
+
     // Check if roracle pallet exists
     if (!api.tx.roracle || !api.tx.roracle.submitOracleValue) {
       throw new Error('Roracle pallet not found. Make sure you are connected to the R-Oracle chain.');
     }
-    
-    const valueU128 = BigInt(Math.floor(value * 1e12)); // Convert to u128 with 12 decimals
+
+    // Values carry the decimals configured for their feed
+    const config = await api.query.roracle.feedConfigs(feed);
+    if (config.isNone) {
+      throw new Error(`Feed ${feed} is not registered`);
+    }
+    const decimals = config.unwrap().decimals.toNumber();
+    const valueU128 = BigInt(Math.floor(value * 10 ** decimals));
     const sourceBytes = new TextEncoder().encode(source);
-    const statusBytes = new TextEncoder().encode(status);
-  
-    const tx = api.tx.roracle.submitOracleValue(valueU128, sourceBytes, statusBytes);
-  
+
+    const tx = api.tx.roracle.submitOracleValue(feed, valueU128, sourceBytes, status, null);
+
     return new Promise((resolve, reject) => {
       tx.signAndSend(signer, ({ status, events }) => {
         if (status.isInBlock || status.isFinalized) {
@@ -113,4 +142,3 @@ export async function submitOracleValue(
     throw new Error(`Failed to submit oracle value: ${error.message}`);
   }
 }
-
@@ -1,10 +1,5 @@
 // Custom types for R-Oracle runtime
-export const types = {
-  OracleValue: {
-    value: 'u128',
-    source: 'Vec<u8>',
-    updated_at: 'u64',
-    status: 'Vec<u8>',
-  },
-};
-
+//
+// Storage and call types are read from the runtime metadata; only types the metadata does not
+// describe belong here.
+export const types = {};
@@ -1,13 +1,17 @@
 //! Chain specification for R-Oracle
 
 use cumulus_primitives_core::ParaId;
+use pallet_roracle::{FeedConfig, FeedInfo, FeedInfoOf, FeedState};
 use r_oracle_runtime::{
-    AccountId, AuraId, Balance, BalancesConfig, CollatorSelectionConfig,
-    RuntimeGenesisConfig, SessionConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
+    AccountId, AuraId, Balance, BalancesConfig, CollatorSelectionConfig, FeedId, RoracleConfig,
+    Runtime, RuntimeGenesisConfig, SessionConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_core::{sr25519, Get, Pair, Public};
+use sp_runtime::{
+    traits::{IdentifyAccount, Verify},
+    Perbill,
+};
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig, Extensions>;
@@ -59,12 +63,10 @@ pub fn development_config() -> ChainSpec {
         move || {
             testnet_genesis(
                 // initial collators.
-                vec![
-                    (
-                        get_account_id_from_seed::<sr25519::Public>("Alice"),
-                        get_collator_keys_from_seed("Alice"),
-                    ),
-                ],
+                vec![(
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_collator_keys_from_seed("Alice"),
+                )],
                 vec![
                     get_account_id_from_seed::<sr25519::Public>("Alice"),
                     get_account_id_from_seed::<sr25519::Public>("Bob"),
@@ -73,6 +75,12 @@ pub fn development_config() -> ChainSpec {
                     get_account_id_from_seed::<sr25519::Public>("Eve"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie"),
                 ],
+                // initial oracle operators.
+                vec![
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_account_id_from_seed::<sr25519::Public>("Bob"),
+                    get_account_id_from_seed::<sr25519::Public>("Charlie"),
+                ],
                 2000.into(),
             )
         },
@@ -88,11 +96,71 @@ pub fn development_config() -> ChainSpec {
     )
 }
 
+/// Feed id of BTC/USD on test networks
+pub const BTC_USD: FeedId = 0;
+
+/// Feed id of ETH/USD on test networks
+pub const ETH_USD: FeedId = 1;
+
+/// Decimals of the feeds on test networks, matching the offchain worker
+pub const FEED_DECIMALS: u8 = pallet_roracle::offchain::PRICE_DECIMALS as u8;
+
+/// Registration of a test network feed with sane bounds given in whole quote units
+fn testnet_feed(
+    feed: FeedId,
+    base: &str,
+    quote: &str,
+    min: u128,
+    max: u128,
+) -> (FeedId, FeedInfoOf<Runtime>, FeedConfig) {
+    let unit = 10u128.pow(FEED_DECIMALS as u32);
+    let info = FeedInfo {
+        base: base
+            .as_bytes()
+            .to_vec()
+            .try_into()
+            .expect("symbol fits MaxSymbolLen; qed"),
+        quote: quote
+            .as_bytes()
+            .to_vec()
+            .try_into()
+            .expect("symbol fits MaxSymbolLen; qed"),
+        description: format!("{} price in {}", base, quote)
+            .into_bytes()
+            .try_into()
+            .expect("description fits MaxDescriptionLen; qed"),
+        min_value: min * unit,
+        max_value: max * unit,
+        state: FeedState::Active,
+    };
+    let config = FeedConfig {
+        heartbeat: Some(3600),
+        max_deviation: Some(Perbill::from_percent(10)),
+        deviation_window: 600,
+        decimals: FEED_DECIMALS,
+        max_observation_age: Some(5 * 60),
+    };
+    (feed, info, config)
+}
+
+/// Unix time in seconds the genesis prices are dated with (2024-01-01 00:00:00 UTC).
+///
+/// The chain has no time before its first block. A fixed date keeps the genesis hash of a spec
+/// the same wherever it is built, so the prices are reported stale until operators submit.
+const GENESIS_VALUES_PUBLISHED_AT: u64 = 1_704_067_200;
+
 fn testnet_genesis(
     invulnerables: Vec<(AccountId, AuraId)>,
     endowed_accounts: Vec<AccountId>,
+    operators: Vec<AccountId>,
     id: ParaId,
 ) -> RuntimeGenesisConfig {
+    // Rounds with fewer operators could only ever close at their deadline
+    assert!(
+        operators.len() as u32 >= <Runtime as pallet_roracle::Config>::MinQuorum::get(),
+        "fewer genesis operators than MinQuorum"
+    );
+
     RuntimeGenesisConfig {
         system: SystemConfig {
             code: WASM_BINARY
@@ -118,8 +186,8 @@ fn testnet_genesis(
                 .into_iter()
                 .map(|(acc, aura)| {
                     (
-                        acc.clone(),                 // account id
-                        acc,                         // validator id
+                        acc.clone(),        // account id
+                        acc,                // validator id
                         session_keys(aura), // session keys
                     )
                 })
@@ -131,7 +199,19 @@ fn testnet_genesis(
             // Assign network admin rights.
             key: Some(get_account_id_from_seed::<sr25519::Public>("Alice")),
         },
-        roracle: Default::default(),
+        roracle: RoracleConfig {
+            operators,
+            feeds: vec![
+                testnet_feed(BTC_USD, "BTC", "USD", 1_000, 1_000_000),
+                testnet_feed(ETH_USD, "ETH", "USD", 10, 100_000),
+            ],
+            // Seed prices so consumers have a value before the first round closes
+            values: vec![
+                (BTC_USD, 60_000 * 10u128.pow(FEED_DECIMALS as u32)),
+                (ETH_USD, 3_000 * 10u128.pow(FEED_DECIMALS as u32)),
+            ],
+            values_published_at: GENESIS_VALUES_PUBLISHED_AT,
+        },
     }
 }
 
@@ -167,6 +247,12 @@ pub fn local_testnet_config() -> ChainSpec {
                     get_account_id_from_seed::<sr25519::Public>("Bob"),
                     get_account_id_from_seed::<sr25519::Public>("Charlie"),
                 ],
+                // initial oracle operators.
+                vec![
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_account_id_from_seed::<sr25519::Public>("Bob"),
+                    get_account_id_from_seed::<sr25519::Public>("Charlie"),
+                ],
                 2000.into(),
             )
         },
@@ -209,4 +295,3 @@ impl Extensions {
         sc_chain_spec::get_extension(chain_spec.extensions())
     }
 }
-
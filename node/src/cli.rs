@@ -1,7 +1,7 @@
 //! CLI for R-Oracle Node
 
-use sc_cli::RunCmd;
 use clap::Parser;
+use sc_cli::RunCmd;
 
 #[derive(Debug, Parser)]
 pub struct Cli {
@@ -47,4 +47,3 @@ pub enum Subcommand {
     #[clap(subcommand)]
     Key(sc_cli::KeySubcommand),
 }
-
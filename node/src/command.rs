@@ -119,12 +119,12 @@ pub fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::Benchmark(cmd)) => {
             let runner = cli.create_runner(cmd)?;
-            runner.sync_run(|config| cmd.run::<sp_runtime::traits::HashingFor<r_oracle_runtime::Block>>(config))
+            runner.sync_run(|config| {
+                cmd.run::<sp_runtime::traits::HashingFor<r_oracle_runtime::Block>>(config)
+            })
         }
         #[cfg(feature = "try-runtime")]
-        Some(Subcommand::TryRuntime(_cmd)) => {
-            Err("try-runtime feature not enabled".into())
-        }
+        Some(Subcommand::TryRuntime(_cmd)) => Err("try-runtime feature not enabled".into()),
         Some(Subcommand::Key(cmd)) => cmd.run(&cli),
         None => {
             let runner = cli.create_runner(&cli.run)?;
@@ -134,4 +134,3 @@ pub fn run() -> sc_cli::Result<()> {
         }
     }
 }
-
@@ -5,9 +5,9 @@
 mod chain_spec;
 mod cli;
 mod command;
+mod rpc;
 mod service;
 
 fn main() -> sc_cli::Result<()> {
     command::run()
 }
-
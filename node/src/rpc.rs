@@ -0,0 +1,353 @@
+//! JSON-RPC extensions for R-Oracle Node
+//!
+//! Exposes the `roracle_*` namespace on top of the `RoracleApi` runtime API, so clients can
+//! read feeds without decoding raw pallet storage.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{
+    core::{async_trait, Error as JsonRpseeError, RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    types::error::{CallError, ErrorObject},
+    RpcModule, SubscriptionSink,
+};
+use pallet_roracle::OracleStatus;
+use pallet_roracle_runtime_api::RoracleApi as RoracleRuntimeApi;
+use r_oracle_runtime::{opaque::Block, FeedId, Hash, OracleValue, RuntimeEvent};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, twox_128};
+
+/// A type representing all RPC extensions.
+pub type RpcExtension = RpcModule<()>;
+
+/// Dependencies of the full node RPC.
+pub struct FullDeps<C> {
+    /// The client instance to use.
+    pub client: Arc<C>,
+    /// Executor for subscription tasks.
+    pub subscription_executor: SubscriptionTaskExecutor,
+}
+
+/// Instantiate all RPC extensions of the full node.
+pub fn create_full<C, BE>(
+    deps: FullDeps<C>,
+) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
+where
+    BE: Backend<Block> + 'static,
+    C: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>
+        + StorageProvider<Block, BE>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: RoracleRuntimeApi<Block, FeedId, OracleValue>,
+{
+    let FullDeps {
+        client,
+        subscription_executor,
+    } = deps;
+
+    let mut module = RpcModule::new(());
+    module.merge(Roracle::<C, BE>::new(client, subscription_executor).into_rpc())?;
+
+    Ok(module)
+}
+
+/// Oracle value in a JSON friendly form.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcOracleValue {
+    /// Raw value, as a decimal string since JSON numbers cannot hold a `u128`
+    pub value: String,
+    /// Number of decimals of the raw value
+    pub decimals: u8,
+    /// Value with its decimals applied, e.g. `"43250.12"`
+    pub price: String,
+    /// Name of the data source
+    pub source: String,
+    /// Unix time in seconds the value was published
+    pub updated_at: u64,
+    /// Unix time in seconds the value was observed at its source
+    pub observed_at: u64,
+    /// Condition of the data source
+    pub status: OracleStatus,
+    /// Spread of the operator submissions, as a raw decimal string like `value`
+    pub confidence: String,
+}
+
+impl RpcOracleValue {
+    /// Convert a value of a feed with `decimals` decimals.
+    pub fn new(value: OracleValue, decimals: u8) -> Self {
+        Self {
+            value: value.value.to_string(),
+            decimals,
+            price: format_decimal(value.value, decimals),
+            source: String::from_utf8_lossy(&value.source).into_owned(),
+            updated_at: value.updated_at,
+            observed_at: value.observed_at,
+            status: value.status,
+            confidence: value.confidence.to_string(),
+        }
+    }
+}
+
+/// Format a raw value with `decimals` decimals, without trailing fraction zeros.
+fn format_decimal(value: u128, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", value, width = decimals as usize + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// History entry with its index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcHistoryEntry {
+    /// Index of the entry in the feed history
+    pub index: u64,
+    /// The recorded value
+    #[serde(flatten)]
+    pub value: RpcOracleValue,
+}
+
+/// Notification sent to `roracle_subscribeValues` subscribers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcValueUpdate {
+    /// Feed that was updated
+    pub feed: FeedId,
+    /// Block the update was included in
+    pub block: Hash,
+    /// The new value
+    #[serde(flatten)]
+    pub value: RpcOracleValue,
+}
+
+/// Oracle RPC methods.
+#[rpc(client, server)]
+pub trait RoracleApi<BlockHash> {
+    /// Latest value of a feed.
+    #[method(name = "roracle_getLatest")]
+    fn get_latest(&self, feed: FeedId, at: Option<BlockHash>) -> RpcResult<Option<RpcOracleValue>>;
+
+    /// Retained history of a feed with an index in `from..to`.
+    #[method(name = "roracle_getHistory")]
+    fn get_history(
+        &self,
+        feed: FeedId,
+        from: u64,
+        to: u64,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<RpcHistoryEntry>>;
+
+    /// Feeds that have a published value.
+    #[method(name = "roracle_listFeeds")]
+    fn list_feeds(&self, at: Option<BlockHash>) -> RpcResult<Vec<FeedId>>;
+
+    /// Current value of every feed, followed by each `ValueUpdated` event in new best blocks.
+    #[subscription(
+        name = "roracle_subscribeValues" => "roracle_value",
+        unsubscribe = "roracle_unsubscribeValues",
+        item = RpcValueUpdate,
+    )]
+    fn subscribe_values(&self);
+}
+
+/// Error code for failed runtime API calls.
+const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    CallError::Custom(ErrorObject::owned(
+        RUNTIME_ERROR,
+        "Unable to query oracle data.",
+        Some(format!("{:?}", err)),
+    ))
+    .into()
+}
+
+/// Decimals runtimes report values with before the runtime API exposed them.
+const DEFAULT_DECIMALS: u8 = 12;
+
+/// Storage key of `frame_system::Events`.
+fn system_events_key() -> StorageKey {
+    StorageKey([twox_128(b"System"), twox_128(b"Events")].concat())
+}
+
+/// Oracle RPC implementation.
+pub struct Roracle<C, BE> {
+    client: Arc<C>,
+    executor: SubscriptionTaskExecutor,
+    _marker: PhantomData<BE>,
+}
+
+impl<C, BE> Roracle<C, BE> {
+    /// Create a new `Roracle` RPC handler.
+    pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+        Self {
+            client,
+            executor,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, BE> Roracle<C, BE>
+where
+    BE: Backend<Block> + 'static,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE>,
+    C::Api: RoracleRuntimeApi<Block, FeedId, OracleValue>,
+{
+    /// Decimals of a feed at `at`, falling back to the default of runtimes without the API.
+    fn decimals(client: &C, at: Hash, feed: FeedId) -> u8 {
+        client
+            .runtime_api()
+            .decimals(at, feed)
+            .unwrap_or(DEFAULT_DECIMALS)
+    }
+
+    /// Latest value of every feed at `at`.
+    fn latest_values(client: &C, at: Hash) -> Vec<RpcValueUpdate> {
+        let api = client.runtime_api();
+        let feeds = api.feeds(at).unwrap_or_default();
+        feeds
+            .into_iter()
+            .filter_map(|feed| {
+                let value = api.latest_value(at, feed).ok()??;
+                let decimals = Self::decimals(client, at, feed);
+                let value = RpcOracleValue::new(value, decimals);
+                Some(RpcValueUpdate {
+                    feed,
+                    block: at,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// `ValueUpdated` events deposited in block `at`.
+    fn value_updates(client: &C, at: Hash) -> Vec<RpcValueUpdate> {
+        let Ok(Some(raw)) = client.storage(at, &system_events_key()) else {
+            return Vec::new();
+        };
+        let Ok(records) =
+            Vec::<frame_system::EventRecord<RuntimeEvent, Hash>>::decode(&mut &raw.0[..])
+        else {
+            return Vec::new();
+        };
+
+        records
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::Roracle(pallet_roracle::Event::ValueUpdated {
+                    feed,
+                    value,
+                    source,
+                    updated_at,
+                    status,
+                    confidence,
+                    observed_at,
+                }) => Some(RpcValueUpdate {
+                    feed,
+                    block: at,
+                    value: RpcOracleValue::new(
+                        OracleValue {
+                            value,
+                            source,
+                            updated_at,
+                            status,
+                            confidence,
+                            observed_at,
+                        },
+                        Self::decimals(client, at, feed),
+                    ),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<C, BE> RoracleApiServer<Hash> for Roracle<C, BE>
+where
+    BE: Backend<Block> + 'static,
+    C: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>
+        + StorageProvider<Block, BE>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: RoracleRuntimeApi<Block, FeedId, OracleValue>,
+{
+    fn get_latest(&self, feed: FeedId, at: Option<Hash>) -> RpcResult<Option<RpcOracleValue>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let value = self
+            .client
+            .runtime_api()
+            .latest_value(at, feed)
+            .map_err(runtime_error)?;
+        let decimals = Self::decimals(&self.client, at, feed);
+        Ok(value.map(|value| RpcOracleValue::new(value, decimals)))
+    }
+
+    fn get_history(
+        &self,
+        feed: FeedId,
+        from: u64,
+        to: u64,
+        at: Option<Hash>,
+    ) -> RpcResult<Vec<RpcHistoryEntry>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let entries = self
+            .client
+            .runtime_api()
+            .history_range(at, feed, from, to)
+            .map_err(runtime_error)?;
+        let decimals = Self::decimals(&self.client, at, feed);
+        Ok(entries
+            .into_iter()
+            .map(|(index, value)| RpcHistoryEntry {
+                index,
+                value: RpcOracleValue::new(value, decimals),
+            })
+            .collect())
+    }
+
+    fn list_feeds(&self, at: Option<Hash>) -> RpcResult<Vec<FeedId>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client.runtime_api().feeds(at).map_err(runtime_error)
+    }
+
+    fn subscribe_values(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        let client = self.client.clone();
+
+        let initial = Self::latest_values(&client, client.info().best_hash);
+        let updates = client
+            .import_notification_stream()
+            .filter(|notification| future::ready(notification.is_new_best))
+            .flat_map(move |notification| {
+                stream::iter(Self::value_updates(&client, notification.hash))
+            });
+        let stream = stream::iter(initial).chain(updates);
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+        self.executor
+            .spawn("roracle-rpc-subscription", Some("rpc"), fut.boxed());
+
+        Ok(())
+    }
+}
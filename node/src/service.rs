@@ -2,7 +2,6 @@
 //! Simplified service implementation
 
 use r_oracle_runtime::{opaque::Block, RuntimeApi};
-use sc_client_api::Backend;
 use sc_executor::NativeElseWasmExecutor;
 use sc_service::{Configuration, PartialComponents, TFullBackend, TFullClient, TaskManager};
 
@@ -55,10 +54,67 @@ pub fn new_partial(
 
 /// Build a new service for a full client.
 pub fn new_full(config: Configuration) -> Result<TaskManager, sc_service::Error> {
-    let (client, backend, _keystore_container, task_manager) =
-        sc_service::new_full_parts::<Block, RuntimeApi, _>(config, None)?;
+    let PartialComponents {
+        client,
+        backend,
+        mut task_manager,
+        import_queue,
+        keystore_container,
+        transaction_pool,
+        ..
+    } = new_partial(&config)?;
+
+    let net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
+
+    let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            net_config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            block_announce_validator_builder: None,
+            warp_sync_params: None,
+        })?;
+
+    if config.offchain_worker.enabled {
+        sc_service::build_offchain_workers(
+            &config,
+            task_manager.spawn_handle(),
+            client.clone(),
+            network.clone(),
+        );
+    }
+
+    let rpc_builder = {
+        let client = client.clone();
+
+        Box::new(move |_deny_unsafe, subscription_executor| {
+            let deps = crate::rpc::FullDeps {
+                client: client.clone(),
+                subscription_executor,
+            };
+            crate::rpc::create_full::<_, FullBackend>(deps).map_err(Into::into)
+        })
+    };
+
+    sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        rpc_builder,
+        client,
+        transaction_pool,
+        task_manager: &mut task_manager,
+        config,
+        keystore: keystore_container.keystore(),
+        backend,
+        network,
+        sync_service,
+        system_rpc_tx,
+        tx_handler_controller,
+        telemetry: None,
+    })?;
 
-    let _client = sc_service::FullClient::new(client, backend, task_manager.spawn_handle());
+    network_starter.start_network();
 
     Ok(task_manager)
 }
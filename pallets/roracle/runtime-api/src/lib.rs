@@ -0,0 +1,61 @@
+//! Runtime API definition for the oracle pallet
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use sp_runtime::{FixedU128, RuntimeDebug};
+use sp_std::prelude::*;
+
+pub use pallet_roracle::candles::Candle;
+
+/// Oracle value together with the decimals of its feed
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DecimalValue<OracleValue> {
+    /// Value as stored, its `value` holding `decimals` decimals
+    pub raw: OracleValue,
+    /// Number of decimals of the feed
+    pub decimals: u8,
+    /// Value as a fixed point number, `None` if it is too large for one
+    pub fixed: Option<FixedU128>,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Read access to oracle feeds without decoding raw storage
+    #[api_version(4)]
+    pub trait RoracleApi<FeedId, OracleValue> where
+        FeedId: Codec,
+        OracleValue: Codec,
+    {
+        /// Latest published value of a feed
+        fn latest_value(feed: FeedId) -> Option<OracleValue>;
+
+        /// Retained history entries of a feed with an index in `from..to`
+        fn history_range(feed: FeedId, from: u64, to: u64) -> Vec<(u64, OracleValue)>;
+
+        /// Retained history entries of a feed updated between `from` and `to` (unix seconds, inclusive)
+        fn history_by_time(feed: FeedId, from: u64, to: u64) -> Vec<(u64, OracleValue)>;
+
+        /// Feeds that have a published value
+        fn feeds() -> Vec<FeedId>;
+
+        /// Whether a feed missed its heartbeat
+        fn is_stale(feed: FeedId) -> bool;
+
+        /// Number of decimals of a feed's values
+        #[api_version(2)]
+        fn decimals(feed: FeedId) -> u8;
+
+        /// Latest published value of a feed in raw and decimal-aware form
+        #[api_version(2)]
+        fn latest_decimal_value(feed: FeedId) -> Option<DecimalValue<OracleValue>>;
+
+        /// Time-weighted average price of a feed over at least the last `window` seconds
+        #[api_version(3)]
+        fn twap(feed: FeedId, window: u64) -> Option<u128>;
+
+        /// Retained candles of a feed for `interval` seconds starting between `from` and `to`
+        /// (unix seconds, inclusive), oldest first
+        #[api_version(4)]
+        fn candles(feed: FeedId, interval: u64, from: u64, to: u64) -> Vec<Candle>;
+    }
+}
@@ -0,0 +1,97 @@
+//! Aggregation of operator submissions into a single round value
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{Percent, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// Strategy used to combine the submissions of a round
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum AggregationMethod {
+    /// Median of all submitted values
+    Median,
+    /// Mean after dropping the given share of submissions from each end
+    TrimmedMean(Percent),
+    /// Median where every submission counts with its operator weight
+    WeightedMedian,
+}
+
+impl Default for AggregationMethod {
+    fn default() -> Self {
+        Self::Median
+    }
+}
+
+/// Aggregate `(value, weight)` samples. Returns `None` if there are no samples.
+pub fn aggregate(method: AggregationMethod, samples: &[(u128, u32)]) -> Option<u128> {
+    let mut values: Vec<u128> = samples.iter().map(|(value, _)| *value).collect();
+    values.sort_unstable();
+
+    match method {
+        AggregationMethod::Median => median(&values),
+        AggregationMethod::TrimmedMean(trim) => trimmed_mean(&values, trim),
+        AggregationMethod::WeightedMedian => {
+            let mut weighted = samples.to_vec();
+            weighted.sort_unstable_by_key(|(value, _)| *value);
+            weighted_median(&weighted)
+        }
+    }
+}
+
+/// Median absolute deviation of `values` from `center`, a spread measure a single outlier cannot
+/// inflate. Zero without values.
+pub fn median_absolute_deviation(values: &[u128], center: u128) -> u128 {
+    let mut deviations: Vec<u128> = values.iter().map(|value| value.abs_diff(center)).collect();
+    deviations.sort_unstable();
+    median(&deviations).unwrap_or_default()
+}
+
+/// Median of sorted values, averaging the two middle values for an even count
+fn median(sorted: &[u128]) -> Option<u128> {
+    let len = sorted.len();
+    if len == 0 {
+        return None;
+    }
+    let mid = len / 2;
+    if len % 2 == 1 {
+        Some(sorted[mid])
+    } else {
+        Some(mean_of_two(sorted[mid - 1], sorted[mid]))
+    }
+}
+
+/// Mean of sorted values without the lowest and highest `trim` share
+fn trimmed_mean(sorted: &[u128], trim: Percent) -> Option<u128> {
+    let len = sorted.len();
+    let cut = trim.mul_floor(len as u32) as usize;
+    if len == 0 || cut.saturating_mul(2) >= len {
+        return median(sorted);
+    }
+
+    let kept = &sorted[cut..len - cut];
+    let sum = kept.iter().fold(0u128, |acc, v| acc.saturating_add(*v));
+    Some(sum / kept.len() as u128)
+}
+
+/// Lower weighted median of samples sorted by value
+fn weighted_median(sorted: &[(u128, u32)]) -> Option<u128> {
+    let total: u64 = sorted.iter().map(|(_, weight)| *weight as u64).sum();
+    if total == 0 {
+        let values: Vec<u128> = sorted.iter().map(|(value, _)| *value).collect();
+        return median(&values);
+    }
+
+    let mut cumulative = 0u64;
+    for (value, weight) in sorted {
+        cumulative += *weight as u64;
+        if cumulative.saturating_mul(2) >= total {
+            return Some(*value);
+        }
+    }
+    sorted.last().map(|(value, _)| *value)
+}
+
+/// Overflow-free mean of two values
+fn mean_of_two(a: u128, b: u128) -> u128 {
+    a / 2 + b / 2 + (a % 2 + b % 2) / 2
+}
@@ -0,0 +1,472 @@
+//! Benchmarking setup for pallet-roracle
+
+use super::*;
+use crate::{
+    offchain::{PriceFormat, PriceSource},
+    traits::BenchmarkHelper,
+    twap::PriceAccumulator,
+};
+use frame_benchmarking::v2::*;
+use frame_support::pallet_prelude::*;
+use frame_system::{
+    offchain::{AppCrypto, SignedPayload},
+    pallet_prelude::BlockNumberFor,
+    RawOrigin,
+};
+use sp_runtime::{
+    traits::{IdentifyAccount, One, TrailingZeroInput},
+    Perbill, RuntimeAppPublic,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+const VALUE: u128 = 1_000_000_000_000;
+/// Largest number of open rounds benchmarked for calls that visit every round
+const MAX_OPEN_ROUNDS: u32 = 100;
+
+fn feed_id<T: Config>() -> T::FeedId {
+    T::FeedId::decode(&mut TrailingZeroInput::zeroes()).expect("infinite input; qed")
+}
+
+/// Feed ids distinct for every `index` the id type can hold
+fn indexed_feed_id<T: Config>(index: u32) -> T::FeedId {
+    let bytes = index.to_le_bytes();
+    T::FeedId::decode(&mut TrailingZeroInput::new(&bytes)).expect("infinite input; qed")
+}
+
+/// Put `n` generated accounts plus `extra` into the operator set
+fn setup_operators<T: Config>(n: u32, extra: Option<T::AccountId>) -> Vec<T::AccountId> {
+    let mut operators: Vec<T::AccountId> = (0..n).map(|i| account("operator", i, SEED)).collect();
+    operators.extend(extra);
+    operators.sort();
+    Operators::<T>::put(BoundedVec::truncate_from(operators.clone()));
+    operators
+}
+
+/// Unix time in seconds the values of `setup_feed` were published and observed at
+const SETUP_TIME: u64 = 1;
+
+fn oracle_value<T: Config>(source: &[u8]) -> OracleValueOf<T> {
+    OracleValue {
+        value: VALUE,
+        source: BoundedVec::truncate_from(source.to_vec()),
+        updated_at: SETUP_TIME,
+        status: OracleStatus::Primary,
+        confidence: 0,
+        observed_at: SETUP_TIME,
+    }
+}
+
+/// Feed metadata of the largest size
+fn feed_info<T: Config>(state: FeedState) -> FeedInfoOf<T> {
+    FeedInfo {
+        base: BoundedVec::truncate_from(vec![b'x'; T::MaxSymbolLen::get() as usize]),
+        quote: BoundedVec::truncate_from(vec![b'x'; T::MaxSymbolLen::get() as usize]),
+        description: BoundedVec::truncate_from(vec![b'x'; T::MaxDescriptionLen::get() as usize]),
+        min_value: 1,
+        max_value: u128::MAX,
+        state,
+    }
+}
+
+fn register_feed<T: Config>(feed: T::FeedId) {
+    Feeds::<T>::insert(feed, feed_info::<T>(FeedState::Active));
+}
+
+/// Put a feed in the state that makes publishing a value most expensive: a history beyond
+/// `MaxHistory` that prunes two entries, a circuit breaker that has to be evaluated, a stale flag
+/// to clear and an accumulator that missed every TWAP boundary since `SETUP_TIME`
+fn setup_feed<T: Config>(feed: T::FeedId) {
+    register_feed::<T>(feed);
+    let value = oracle_value::<T>(b"benchmark");
+    History::<T>::insert(feed, 0, &value);
+    History::<T>::insert(feed, 1, &value);
+    HistoryIndex::<T>::insert(feed, T::MaxHistory::get() as u64 + 1);
+    LastValue::<T>::insert(feed, &value);
+    StaleFeeds::<T>::insert(feed, value.updated_at);
+    PriceAccumulators::<T>::insert(
+        feed,
+        PriceAccumulator {
+            cumulative: 0,
+            last_value: VALUE,
+            last_update: SETUP_TIME,
+        },
+    );
+    FeedConfigs::<T>::insert(
+        feed,
+        FeedConfig {
+            heartbeat: Some(1),
+            max_deviation: Some(Perbill::one()),
+            deviation_window: u64::MAX,
+            decimals: 12,
+            max_observation_age: Some(u64::MAX),
+        },
+    );
+
+    // Every TWAP bucket gets an observation on the next publication
+    let granularity = T::TwapGranularity::get().max(1);
+    let buckets = T::TwapBuckets::get() as u64;
+    T::BenchmarkHelper::set_time(
+        SETUP_TIME.saturating_add(granularity.saturating_mul(buckets.saturating_add(1))),
+    );
+}
+
+/// Open a round of `feed` with a submission from every operator but the first
+fn fill_round<T: Config>(feed: T::FeedId, operators: &[T::AccountId], source: &[u8]) {
+    for operator in operators.iter().skip(1) {
+        Pallet::<T>::do_submit(
+            operator.clone(),
+            feed,
+            VALUE,
+            source.to_vec(),
+            OracleStatus::Primary,
+            None,
+        )
+        .expect("quorum is not reached before the last operator submits; qed");
+    }
+}
+
+/// Store an open round of `feed` closing at `deadline` with a submission from every operator
+fn open_round<T: Config>(feed: T::FeedId, operators: &[T::AccountId], deadline: BlockNumberFor<T>) {
+    let source = BoundedVec::truncate_from(vec![b'x'; T::MaxSourceLen::get() as usize]);
+    let submissions = operators
+        .iter()
+        .map(|operator| Submission {
+            operator: operator.clone(),
+            value: VALUE,
+            source: source.clone(),
+            status: OracleStatus::Primary,
+            observed_at: Pallet::<T>::now(),
+        })
+        .collect::<Vec<_>>();
+    let id = NextRoundId::<T>::mutate(feed, |id| {
+        let current = *id;
+        *id += 1;
+        current
+    });
+    let submissions = BoundedVec::truncate_from(submissions);
+    Rounds::<T>::insert(
+        feed,
+        Round {
+            id,
+            deadline,
+            submissions,
+        },
+    );
+    RoundDeadlines::<T>::insert(deadline, feed, ());
+}
+
+fn admin_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+    T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+}
+
+/// Number of operators whose submissions finalize a round
+fn quorum<T: Config>() -> u32 {
+    T::MinQuorum::get().clamp(1, T::MaxOperators::get())
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn submit_oracle_value(s: Linear<1, { T::MaxSourceLen::get() }>) {
+        let feed = feed_id::<T>();
+        let operators = setup_operators::<T>(quorum::<T>(), None);
+        let source = vec![b'x'; s as usize];
+        setup_feed::<T>(feed);
+        fill_round::<T>(feed, &operators, &source);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(operators[0].clone()),
+            feed,
+            VALUE,
+            source,
+            OracleStatus::Primary,
+            None,
+        );
+
+        assert!(Rounds::<T>::get(feed).is_none());
+        assert_eq!(HistoryStart::<T>::get(feed), 2);
+    }
+
+    #[benchmark]
+    fn submit_oracle_value_unsigned_with_signed_payload(
+        s: Linear<1, { T::MaxSourceLen::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let key =
+            <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::generate_pair(
+                None,
+            );
+        let generic: <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic =
+            key.into();
+        let public: T::Public = generic.into();
+        let who = public.clone().into_account();
+
+        let feed = feed_id::<T>();
+        let mut operators = setup_operators::<T>(quorum::<T>() - 1, Some(who.clone()));
+        // The key's operator submits last
+        operators.retain(|operator| operator != &who);
+        operators.insert(0, who);
+        let source = vec![b'x'; s as usize];
+        setup_feed::<T>(feed);
+        fill_round::<T>(feed, &operators, &source);
+
+        let payload = OraclePayload {
+            feed,
+            round: Pallet::<T>::current_round(feed),
+            value: VALUE,
+            source,
+            status: OracleStatus::Primary,
+            observed_at: Some(Pallet::<T>::now()),
+            block_number: frame_system::Pallet::<T>::block_number(),
+            public,
+        };
+        let signature = SignedPayload::<T>::sign::<T::AuthorityId>(&payload)
+            .ok_or(BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(RawOrigin::None, payload, signature);
+
+        assert!(Rounds::<T>::get(feed).is_none());
+        assert_eq!(HistoryStart::<T>::get(feed), 2);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn add_operator() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        setup_operators::<T>(T::MaxOperators::get() - 1, None);
+        let who: T::AccountId = account("new", 0, SEED);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, who.clone());
+
+        assert!(Pallet::<T>::is_operator(&who));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn remove_operator() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let operators = setup_operators::<T>(T::MaxOperators::get(), None);
+        // Removing the first operator shifts every other one
+        let who = operators[0].clone();
+        OperatorWeights::<T>::insert(&who, 2);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, who.clone());
+
+        assert!(!Pallet::<T>::is_operator(&who));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn rotate_operator(r: Linear<0, MAX_OPEN_ROUNDS>) -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let operators = setup_operators::<T>(T::MaxOperators::get(), None);
+        // The old key submitted last to every open round
+        let old = operators[operators.len() - 1].clone();
+        let new: T::AccountId = account("new", 0, SEED);
+        OperatorWeights::<T>::insert(&old, 2);
+        let deadline = frame_system::Pallet::<T>::block_number() + One::one();
+        for feed in (0..r).map(indexed_feed_id::<T>) {
+            open_round::<T>(feed, &operators, deadline);
+        }
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, old.clone(), new.clone(), r);
+
+        assert!(!Pallet::<T>::is_operator(&old));
+        assert_eq!(OperatorWeights::<T>::get(&new), 2);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_operator_weight() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let operators = setup_operators::<T>(T::MaxOperators::get(), None);
+        let who = operators[operators.len() - 1].clone();
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, who.clone(), 5);
+
+        assert_eq!(OperatorWeights::<T>::get(&who), 5);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_feed_config() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let feed = feed_id::<T>();
+        // Feeds with values are checked to keep their decimals
+        setup_feed::<T>(feed);
+        let config = FeedConfig {
+            heartbeat: Some(60),
+            max_deviation: Some(Perbill::from_percent(10)),
+            deviation_window: 600,
+            decimals: 12,
+            max_observation_age: Some(60),
+        };
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, feed, config.clone());
+
+        assert_eq!(FeedConfigs::<T>::get(feed), Some(config));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn confirm_quarantined() {
+        let feed = feed_id::<T>();
+        let operators = setup_operators::<T>(T::MaxOperators::get(), None);
+        // Every other operator reported the value, so the confirming one is checked against all
+        let reporters = operators[1..].to_vec();
+        setup_feed::<T>(feed);
+        Quarantine::<T>::insert(
+            feed,
+            QuarantinedValue {
+                value: oracle_value::<T>(&vec![b'x'; T::MaxSourceLen::get() as usize]),
+                deviation: Perbill::from_percent(50),
+                reporters: BoundedVec::truncate_from(reporters),
+            },
+        );
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(operators[0].clone()), feed);
+
+        assert!(Quarantine::<T>::get(feed).is_none());
+    }
+
+    #[benchmark]
+    fn set_feed_sources(
+        n: Linear<0, { T::MaxSources::get().min(T::MaxFailoverLevel::get() as u32 + 1) }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let feed = feed_id::<T>();
+        register_feed::<T>(feed);
+        let source = PriceSource {
+            name: BoundedVec::truncate_from(vec![b'x'; T::MaxSourceLen::get() as usize]),
+            url: BoundedVec::truncate_from(vec![b'x'; T::MaxUrlLen::get() as usize]),
+            format: PriceFormat::Binance,
+        };
+        let sources = BoundedVec::truncate_from(vec![source; n as usize]);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, feed, sources);
+
+        assert_eq!(FeedSources::<T>::get(feed).len(), n as usize);
+        Ok(())
+    }
+
+    #[benchmark]
+    fn create_feed() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let feed = feed_id::<T>();
+        // Genesis may already register the feed
+        Feeds::<T>::remove(feed);
+        let info = feed_info::<T>(FeedState::Active);
+
+        #[extrinsic_call]
+        _(
+            origin as T::RuntimeOrigin,
+            feed,
+            info.clone(),
+            FeedConfig::default(),
+        );
+
+        assert_eq!(Feeds::<T>::get(feed), Some(info));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn update_feed() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let feed = feed_id::<T>();
+        // Feeds with values are checked to keep their decimals
+        setup_feed::<T>(feed);
+        let info = feed_info::<T>(FeedState::Paused);
+        let config = FeedConfig {
+            decimals: 12,
+            ..Default::default()
+        };
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, feed, info.clone(), config);
+
+        assert_eq!(Feeds::<T>::get(feed), Some(info));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn deprecate_feed() -> Result<(), BenchmarkError> {
+        let origin = admin_origin::<T>()?;
+        let feed = feed_id::<T>();
+        let operators = setup_operators::<T>(quorum::<T>(), None);
+        setup_feed::<T>(feed);
+        fill_round::<T>(feed, &operators, b"benchmark");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, feed);
+
+        assert_eq!(
+            Feeds::<T>::get(feed).map(|info| info.state),
+            Some(FeedState::Deprecated)
+        );
+        Ok(())
+    }
+
+    #[benchmark]
+    fn submit_batch(
+        n: Linear<1, { T::MaxBatchSize::get() }>,
+        s: Linear<1, { T::MaxSourceLen::get() }>,
+    ) {
+        let operators = setup_operators::<T>(quorum::<T>(), None);
+        let source = vec![b'x'; s as usize];
+        let feeds: Vec<T::FeedId> = (0..n).map(indexed_feed_id::<T>).collect();
+        for feed in &feeds {
+            setup_feed::<T>(*feed);
+            fill_round::<T>(*feed, &operators, &source);
+        }
+        let values: Vec<(T::FeedId, u128, OracleStatus)> = feeds
+            .iter()
+            .map(|feed| (*feed, VALUE, OracleStatus::Primary))
+            .collect();
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(operators[0].clone()),
+            BoundedVec::truncate_from(values),
+            source,
+            None,
+        );
+
+        for feed in &feeds {
+            assert!(Rounds::<T>::get(feed).is_none());
+        }
+    }
+
+    #[benchmark]
+    fn finalize_expired_rounds(n: Linear<0, { T::MaxRoundsPerBlock::get() }>) {
+        let operators = setup_operators::<T>(T::MaxOperators::get(), None);
+        let deadline = frame_system::Pallet::<T>::block_number();
+        let feeds: Vec<T::FeedId> = (0..n).map(indexed_feed_id::<T>).collect();
+        for feed in &feeds {
+            setup_feed::<T>(*feed);
+            // Every operator reported, which bounds the rounds that time out short of quorum
+            open_round::<T>(*feed, &operators, deadline);
+        }
+
+        #[block]
+        {
+            Pallet::<T>::finalize_expired_rounds(deadline);
+        }
+
+        for feed in &feeds {
+            assert!(Rounds::<T>::get(feed).is_none());
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::Test);
+}
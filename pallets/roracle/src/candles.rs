@@ -0,0 +1,98 @@
+//! Open/high/low/close candles of published values
+//!
+//! Every published value is folded into the current candle of each of the `CandleIntervals` of
+//! its feed. Candles are kept in a ring of `CandleRetention` slots per feed and interval, keyed by
+//! candle number modulo `CandleRetention`, so charts read a bounded number of entries instead of
+//! rebuilding candles from `History`.
+
+use crate::pallet::*;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// Values published within one interval
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Candle {
+    /// Unix time in seconds the interval starts at
+    pub start: u64,
+    pub open: u128,
+    pub high: u128,
+    pub low: u128,
+    pub close: u128,
+    /// Number of values published within the interval
+    pub count: u32,
+}
+
+impl Candle {
+    /// Candle of a single value
+    fn new(start: u64, value: u128) -> Self {
+        Self {
+            start,
+            open: value,
+            high: value,
+            low: value,
+            close: value,
+            count: 1,
+        }
+    }
+
+    /// Account a later value of the same interval
+    fn push(&mut self, value: u128) {
+        self.high = self.high.max(value);
+        self.low = self.low.min(value);
+        self.close = value;
+        self.count = self.count.saturating_add(1);
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Fold a value published at `at` into the current candle of every interval of a feed
+    pub(crate) fn record_candles(feed: T::FeedId, value: u128, at: u64) {
+        let retention = T::CandleRetention::get() as u64;
+        if retention == 0 {
+            return;
+        }
+
+        for interval in T::CandleIntervals::get()
+            .into_iter()
+            .filter(|interval| *interval > 0)
+        {
+            let number = at / interval;
+            let start = number * interval;
+            let slot = (number % retention) as u32;
+            Candles::<T>::mutate((feed, interval, slot), |candle| match candle {
+                Some(candle) if candle.start == start => candle.push(value),
+                // Values are published in time order, so an older candle in the slot is replaced
+                _ => *candle = Some(Candle::new(start, value)),
+            });
+        }
+    }
+
+    /// Retained candles of a feed for `interval` starting between `from` and `to` (unix seconds,
+    /// inclusive), oldest first.
+    ///
+    /// Intervals without published values have no candle. Returns nothing for an interval that
+    /// is not one of `CandleIntervals`.
+    pub fn candles(feed: T::FeedId, interval: u64, from: u64, to: u64) -> Vec<Candle> {
+        let retention = T::CandleRetention::get() as u64;
+        if retention == 0 || interval == 0 || !T::CandleIntervals::get().contains(&interval) {
+            return Vec::new();
+        }
+
+        let latest = Self::now() / interval;
+        // First candle starting at or after `from`
+        let first = from / interval + u64::from(from % interval != 0);
+        let first = first.max(latest.saturating_sub(retention - 1));
+        let last = (to / interval).min(latest);
+
+        (first..=last)
+            .filter_map(|number| {
+                let candle = Candles::<T>::get((feed, interval, (number % retention) as u32))?;
+                // The slot may still hold a candle of an earlier round of the ring
+                (candle.start == number * interval).then_some(candle)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,32 @@
+//! Conversions of raw feed values, which carry the number of decimals configured for their feed
+
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+/// Number of decimals of [`FixedU128`]
+pub const FIXED_DECIMALS: u8 = 18;
+
+/// Express `value` with `from` decimals in `to` decimals, truncating dropped digits.
+/// Returns `None` if the result does not fit a `u128`.
+pub fn rescale(value: u128, from: u8, to: u8) -> Option<u128> {
+    if to >= from {
+        value.checked_mul(10u128.checked_pow((to - from) as u32)?)
+    } else {
+        // Dropping more digits than a `u128` has leaves nothing
+        Some(
+            10u128
+                .checked_pow((from - to) as u32)
+                .map_or(0, |divisor| value / divisor),
+        )
+    }
+}
+
+/// Convert a raw value with `decimals` decimals into a fixed point number, truncating digits
+/// beyond [`FIXED_DECIMALS`]. Returns `None` if the value is too large.
+pub fn to_fixed(value: u128, decimals: u8) -> Option<FixedU128> {
+    rescale(value, decimals, FIXED_DECIMALS).map(FixedU128::from_inner)
+}
+
+/// Convert a fixed point number into a raw value with `decimals` decimals
+pub fn from_fixed(value: FixedU128, decimals: u8) -> Option<u128> {
+    rescale(value.into_inner(), FIXED_DECIMALS, decimals)
+}
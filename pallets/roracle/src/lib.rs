@@ -2,60 +2,555 @@
 
 pub use pallet::*;
 
+pub mod aggregation;
+pub mod candles;
+pub mod fixed;
+pub mod migrations;
+pub mod offchain;
+pub mod traits;
+pub mod twap;
+pub mod weights;
+pub use traits::{OnNewValue, PriceProvider};
+pub use weights::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[frame_support::pallet]
 pub mod pallet {
-    use codec::{Decode, Encode};
-    use frame_support::{
+    use codec::{Decode, Encode, MaxEncodedLen};
+    use frame_support::{pallet_prelude::*, storage::with_storage_layer, traits::UnixTime};
+    use frame_system::{
+        offchain::{AppCrypto, CreateSignedTransaction, SignedPayload, SigningTypes},
         pallet_prelude::*,
-        traits::Time,
     };
-    use frame_system::pallet_prelude::*;
     use scale_info::TypeInfo;
+    use sp_runtime::{
+        traits::{IdentifyAccount, One, SaturatedConversion, Saturating},
+        FixedU128, Perbill,
+    };
     use sp_std::prelude::*;
 
+    #[cfg(any(feature = "try-runtime", test))]
+    use sp_runtime::TryRuntimeError;
+
+    use crate::{
+        aggregation::{self, AggregationMethod},
+        candles::Candle,
+        fixed,
+        offchain::PriceSource,
+        traits::OnNewValue,
+        twap::{Observation, PriceAccumulator},
+        WeightInfo,
+    };
+
+    /// The in-code storage version.
+    pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
-        
+
         /// The timestamp provider
-        type TimeProvider: Time;
+        type TimeProvider: UnixTime;
+
+        /// Identifier of a price feed (e.g. BTC/USD, ETH/USD)
+        type FeedId: Parameter + Member + MaxEncodedLen + Copy + Ord + MaybeSerializeDeserialize;
+
+        /// Origin allowed to manage the operator set
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum number of oracle operators
+        #[pallet::constant]
+        type MaxOperators: Get<u32>;
+
+        /// Number of submissions that finalizes a round
+        #[pallet::constant]
+        type MinQuorum: Get<u32>;
+
+        /// Number of blocks after which an open round is finalized regardless of quorum
+        #[pallet::constant]
+        type RoundTimeout: Get<BlockNumberFor<Self>>;
+
+        /// How submissions of a round are combined into a single value
+        #[pallet::constant]
+        type Aggregation: Get<AggregationMethod>;
+
+        /// Maximum length of a source name
+        #[pallet::constant]
+        type MaxSourceLen: Get<u32>;
+
+        /// Number of history entries kept per feed
+        #[pallet::constant]
+        type MaxHistory: Get<u32>;
+
+        /// Deepest failover level a submission may report
+        #[pallet::constant]
+        type MaxFailoverLevel: Get<u8>;
+
+        /// Keys the offchain worker signs its submissions with
+        type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+        /// Maximum number of price sources per feed
+        #[pallet::constant]
+        type MaxSources: Get<u32>;
+
+        /// Maximum length of a price source URL
+        #[pallet::constant]
+        type MaxUrlLen: Get<u32>;
+
+        /// Maximum length of a feed's base and quote asset symbols
+        #[pallet::constant]
+        type MaxSymbolLen: Get<u32>;
+
+        /// Maximum length of a feed description
+        #[pallet::constant]
+        type MaxDescriptionLen: Get<u32>;
+
+        /// Number of blocks between two price fetches of the offchain worker
+        #[pallet::constant]
+        type FetchInterval: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of unsigned submissions included per block
+        #[pallet::constant]
+        type MaxUnsignedPerBlock: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet
+        type WeightInfo: WeightInfo;
+
+        /// Priority of an unsigned submission signed in the current block
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+
+        /// Number of blocks after which a signed payload can no longer be submitted
+        #[pallet::constant]
+        type UnsignedLongevity: Get<BlockNumberFor<Self>>;
+
+        /// Seconds between two TWAP observations, to which TWAP windows are rounded up
+        #[pallet::constant]
+        type TwapGranularity: Get<u64>;
+
+        /// Number of TWAP observations kept per feed, bounding the longest TWAP window
+        #[pallet::constant]
+        type TwapBuckets: Get<u32>;
+
+        /// Distinct lengths in seconds of the intervals candles are kept for
+        #[pallet::constant]
+        type CandleIntervals: Get<Vec<u64>>;
+
+        /// Number of candles kept per feed and interval
+        #[pallet::constant]
+        type CandleRetention: Get<u32>;
+
+        /// Handler notified of every published value
+        type OnNewValue: OnNewValue<Self::FeedId>;
+
+        /// Maximum number of values in a `submit_batch` call
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
+
+        /// Maximum number of feeds checked against their heartbeat per block
+        #[pallet::constant]
+        type MaxHeartbeatChecks: Get<u32>;
+
+        /// Maximum number of expired rounds closed per block
+        #[pallet::constant]
+        type MaxRoundsPerBlock: Get<u32>;
+
+        /// Runtime hooks used by the benchmarks
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: crate::traits::BenchmarkHelper;
+    }
+
+    /// Source name bounded by `MaxSourceLen`
+    pub type SourceOf<T> = BoundedVec<u8, <T as Config>::MaxSourceLen>;
+
+    /// Oracle value as stored for a runtime
+    pub type OracleValueOf<T> = OracleValue<<T as Config>::MaxSourceLen>;
+
+    /// Price source as stored for a runtime
+    pub type PriceSourceOf<T> = PriceSource<<T as Config>::MaxSourceLen, <T as Config>::MaxUrlLen>;
+
+    /// Feed metadata as stored for a runtime
+    pub type FeedInfoOf<T> =
+        FeedInfo<<T as Config>::MaxSymbolLen, <T as Config>::MaxDescriptionLen>;
+
+    /// Condition of the data source a value was obtained from
+    #[derive(
+        Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub enum OracleStatus {
+        /// Value comes from the primary source
+        #[default]
+        Primary,
+        /// Value comes from a fallback source, 1 being the first fallback
+        Failover(u8),
+        /// Sources were only partially available or disagreed
+        Degraded,
+        /// Value was entered manually
+        Manual,
+        /// Value is older than its feed allows; only set by the pallet
+        Stale,
+    }
+
+    impl OracleStatus {
+        /// Number of sources that failed before this value was obtained
+        pub fn failover_depth(&self) -> u8 {
+            match self {
+                OracleStatus::Failover(level) => *level,
+                _ => 0,
+            }
+        }
     }
 
     /// Oracle Value struct
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(SourceLimit))]
+    #[codec(mel_bound())]
+    pub struct OracleValue<SourceLimit: Get<u32>> {
+        pub value: u128,
+        pub source: BoundedVec<u8, SourceLimit>,
+        /// Unix time in seconds the value was published on chain
+        pub updated_at: u64,
+        pub status: OracleStatus,
+        /// Spread of the submissions the value was aggregated from, in the decimals of the value
+        pub confidence: u128,
+        /// Unix time in seconds the value was observed at its source
+        pub observed_at: u64,
+    }
+
+    /// Submission signed by an operator key and sent as an unsigned transaction
     #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
-    pub struct OracleValue {
+    pub struct OraclePayload<Public, BlockNumber, FeedId> {
+        pub feed: FeedId,
+        /// Round of the feed the value is submitted to, so it cannot be replayed in later rounds
+        pub round: u64,
         pub value: u128,
         pub source: Vec<u8>,
-        pub updated_at: u64,
-        pub status: Vec<u8>,
+        pub status: OracleStatus,
+        /// Unix time in seconds the value was observed at its source, if known
+        pub observed_at: Option<u64>,
+        /// Block the payload was signed at
+        pub block_number: BlockNumber,
+        pub public: Public,
+    }
+
+    /// Signed payload as used by a runtime
+    pub type OraclePayloadOf<T> =
+        OraclePayload<<T as SigningTypes>::Public, BlockNumberFor<T>, <T as Config>::FeedId>;
+
+    impl<T: SigningTypes, FeedId: Encode> SignedPayload<T>
+        for OraclePayload<T::Public, BlockNumberFor<T>, FeedId>
+    {
+        fn public(&self) -> T::Public {
+            self.public.clone()
+        }
+    }
+
+    /// Largest number of decimals a `u128` value can carry
+    pub const MAX_DECIMALS: u8 = 38;
+
+    /// Per-feed parameters set by the admin origin
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub struct FeedConfig {
+        /// Maximum age in seconds before the feed is considered stale
+        pub heartbeat: Option<u64>,
+        /// Maximum relative change from the last accepted value before the circuit breaker trips
+        pub max_deviation: Option<Perbill>,
+        /// Seconds since the last accepted value during which `max_deviation` applies
+        pub deviation_window: u64,
+        /// Number of decimals of the feed's values
+        pub decimals: u8,
+        /// Maximum age in seconds of a submitted value's observation time
+        pub max_observation_age: Option<u64>,
+    }
+
+    impl Default for FeedConfig {
+        fn default() -> Self {
+            Self {
+                heartbeat: None,
+                max_deviation: None,
+                deviation_window: 0,
+                decimals: crate::offchain::PRICE_DECIMALS as u8,
+                max_observation_age: None,
+            }
+        }
+    }
+
+    /// Lifecycle state of a registered feed
+    #[derive(
+        Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub enum FeedState {
+        /// Feed accepts submissions
+        #[default]
+        Active,
+        /// Feed temporarily rejects submissions
+        Paused,
+        /// Feed is retired for good; its published values remain readable
+        Deprecated,
+    }
+
+    /// Metadata of a registered feed.
+    ///
+    /// Decimals and heartbeat live in the feed's [`FeedConfig`], which is set along with it.
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "std", serde(bound = ""))]
+    #[scale_info(skip_type_params(SymbolLimit, DescriptionLimit))]
+    #[codec(mel_bound())]
+    pub struct FeedInfo<SymbolLimit: Get<u32>, DescriptionLimit: Get<u32>> {
+        /// Symbol of the priced asset, e.g. `BTC`
+        pub base: BoundedVec<u8, SymbolLimit>,
+        /// Symbol of the asset the price is expressed in, e.g. `USD`
+        pub quote: BoundedVec<u8, SymbolLimit>,
+        pub description: BoundedVec<u8, DescriptionLimit>,
+        /// Smallest value accepted from a submitter
+        pub min_value: u128,
+        /// Largest value accepted from a submitter
+        pub max_value: u128,
+        pub state: FeedState,
+    }
+
+    /// Value held back by the circuit breaker until it is confirmed
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct QuarantinedValue<T: Config> {
+        pub value: OracleValueOf<T>,
+        /// Deviation from the last accepted value, saturating at 100%
+        pub deviation: Perbill,
+        /// Operators whose submissions produced the value
+        pub reporters: BoundedVec<T::AccountId, T::MaxOperators>,
+    }
+
+    /// A single operator submission within a round
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct Submission<T: Config> {
+        pub operator: T::AccountId,
+        pub value: u128,
+        pub source: SourceOf<T>,
+        pub status: OracleStatus,
+        /// Unix time in seconds the value was observed at its source
+        pub observed_at: u64,
+    }
+
+    /// Open aggregation round of a feed
+    #[derive(
+        Encode,
+        Decode,
+        CloneNoBound,
+        PartialEqNoBound,
+        EqNoBound,
+        RuntimeDebugNoBound,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct Round<T: Config> {
+        pub id: u64,
+        pub deadline: BlockNumberFor<T>,
+        pub submissions: BoundedVec<Submission<T>, T::MaxOperators>,
     }
 
-    /// Storage: Last oracle value
+    #[pallet::type_value]
+    pub fn DefaultOperatorWeight() -> u32 {
+        1
+    }
+
+    /// Storage: Last oracle value per feed
     #[pallet::storage]
     #[pallet::getter(fn last_value)]
-    pub type LastValue<T: Config> = StorageValue<_, OracleValue, OptionQuery>;
+    pub type LastValue<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::FeedId, OracleValueOf<T>, OptionQuery>;
 
-    /// Storage: History of oracle values
+    /// Storage: History of oracle values per feed
     #[pallet::storage]
     #[pallet::getter(fn history)]
-    pub type History<T: Config> = StorageMap<
+    pub type History<T: Config> = StorageDoubleMap<
         _,
         Blake2_128Concat,
+        T::FeedId,
+        Blake2_128Concat,
         u64,
-        OracleValue,
-        ValueQuery,
-   >;
+        OracleValueOf<T>,
+        OptionQuery,
+    >;
 
-    /// Storage: History index counter
+    /// Storage: Accounts allowed to submit oracle values, kept sorted
+    #[pallet::storage]
+    #[pallet::getter(fn operators)]
+    pub type Operators<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxOperators>, ValueQuery>;
+
+    /// Storage: Weight of each operator in weighted median aggregation
+    #[pallet::storage]
+    #[pallet::getter(fn operator_weight)]
+    pub type OperatorWeights<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery, DefaultOperatorWeight>;
+
+    /// Storage: Open round per feed
+    #[pallet::storage]
+    #[pallet::getter(fn round)]
+    pub type Rounds<T: Config> = StorageMap<_, Blake2_128Concat, T::FeedId, Round<T>, OptionQuery>;
+
+    /// Storage: Feeds with an open round closing at a block
+    #[pallet::storage]
+    pub type RoundDeadlines<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        T::FeedId,
+        (),
+        OptionQuery,
+    >;
+
+    /// Storage: Earliest deadline block with rounds left open once `MaxRoundsPerBlock` was reached
+    #[pallet::storage]
+    pub type RoundDeadlineCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+    /// Storage: Id of the next round per feed
+    #[pallet::storage]
+    #[pallet::getter(fn next_round_id)]
+    pub type NextRoundId<T: Config> = StorageMap<_, Blake2_128Concat, T::FeedId, u64, ValueQuery>;
+
+    /// Storage: History index counter per feed
     #[pallet::storage]
     #[pallet::getter(fn history_index)]
-    pub type HistoryIndex<T: Config> = StorageValue<_, u64, ValueQuery>;
+    pub type HistoryIndex<T: Config> = StorageMap<_, Blake2_128Concat, T::FeedId, u64, ValueQuery>;
+
+    /// Storage: Configuration per feed
+    #[pallet::storage]
+    #[pallet::getter(fn feed_config)]
+    pub type FeedConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::FeedId, FeedConfig, OptionQuery>;
+
+    /// Storage: Value held back by the circuit breaker per feed
+    #[pallet::storage]
+    #[pallet::getter(fn quarantined)]
+    pub type Quarantine<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::FeedId, QuarantinedValue<T>, OptionQuery>;
+
+    /// Storage: Feeds whose last value is older than their heartbeat, with that value's timestamp
+    #[pallet::storage]
+    #[pallet::getter(fn stale_since)]
+    pub type StaleFeeds<T: Config> = StorageMap<_, Blake2_128Concat, T::FeedId, u64, OptionQuery>;
+
+    /// Storage: Last feed checked against its heartbeat, if the check continues in the next block
+    #[pallet::storage]
+    pub type HeartbeatCursor<T: Config> = StorageValue<_, T::FeedId, OptionQuery>;
+
+    /// Storage: Index of the oldest retained history entry per feed
+    #[pallet::storage]
+    #[pallet::getter(fn history_start)]
+    pub type HistoryStart<T: Config> = StorageMap<_, Blake2_128Concat, T::FeedId, u64, ValueQuery>;
+
+    /// Storage: Price sources per feed in priority order, fetched by the offchain worker
+    #[pallet::storage]
+    #[pallet::getter(fn feed_sources)]
+    pub type FeedSources<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::FeedId,
+        BoundedVec<PriceSourceOf<T>, T::MaxSources>,
+        ValueQuery,
+    >;
+
+    /// Storage: Registered feeds
+    #[pallet::storage]
+    #[pallet::getter(fn feed_info)]
+    pub type Feeds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::FeedId, FeedInfoOf<T>, OptionQuery>;
+
+    /// Storage: Number of unsigned submissions in the current block, cleared in `on_finalize`
+    #[pallet::storage]
+    #[pallet::getter(fn unsigned_submissions)]
+    pub type UnsignedSubmissions<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Storage: Cumulative price per feed for TWAP computation
+    #[pallet::storage]
+    #[pallet::getter(fn price_accumulator)]
+    pub type PriceAccumulators<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::FeedId, PriceAccumulator, OptionQuery>;
+
+    /// Storage: Cumulative price at the latest `TwapBuckets` granularity boundaries per feed,
+    /// keyed by boundary number modulo `TwapBuckets`
+    #[pallet::storage]
+    #[pallet::getter(fn twap_observation)]
+    pub type TwapObservations<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::FeedId,
+        Blake2_128Concat,
+        u32,
+        Observation,
+        OptionQuery,
+    >;
+
+    /// Storage: Latest `CandleRetention` candles per feed and interval, keyed by candle number
+    /// modulo `CandleRetention`
+    #[pallet::storage]
+    #[pallet::getter(fn candle)]
+    pub type Candles<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, T::FeedId>,
+            NMapKey<Blake2_128Concat, u64>,
+            NMapKey<Blake2_128Concat, u32>,
+        ),
+        Candle,
+        OptionQuery,
+    >;
 
     // Pallets use events to inform users when important changes are made.
     #[pallet::event]
@@ -63,10 +558,84 @@ pub mod pallet {
     pub enum Event<T: Config> {
         /// Oracle value was updated
         ValueUpdated {
+            feed: T::FeedId,
             value: u128,
-            source: Vec<u8>,
+            source: SourceOf<T>,
             updated_at: u64,
-            status: Vec<u8>,
+            status: OracleStatus,
+            confidence: u128,
+            observed_at: u64,
+        },
+        /// An operator was added
+        OperatorAdded { who: T::AccountId },
+        /// An operator was removed
+        OperatorRemoved { who: T::AccountId },
+        /// An operator key was replaced by a new one
+        OperatorRotated {
+            old: T::AccountId,
+            new: T::AccountId,
+        },
+        /// An operator weight was changed
+        OperatorWeightSet { who: T::AccountId, weight: u32 },
+        /// An operator submitted a value to an open round
+        SubmissionReceived {
+            feed: T::FeedId,
+            round: u64,
+            who: T::AccountId,
+            value: u128,
+        },
+        /// A round was closed and its aggregated value published
+        RoundFinalized {
+            feed: T::FeedId,
+            round: u64,
+            value: u128,
+            participants: u32,
+        },
+        /// A round was closed without publishing since its value was observed before the last
+        /// published one
+        RoundOutOfOrder {
+            feed: T::FeedId,
+            round: u64,
+            observed_at: u64,
+        },
+        /// Oldest history entries of a feed were removed
+        HistoryPruned {
+            feed: T::FeedId,
+            removed: u64,
+            oldest: u64,
+        },
+        /// Configuration of a feed was changed
+        FeedConfigSet { feed: T::FeedId, config: FeedConfig },
+        /// A feed missed its heartbeat
+        FeedStale { feed: T::FeedId, last_update: u64 },
+        /// A stale feed received a new value
+        FeedRecovered { feed: T::FeedId },
+        /// A value deviated too far from the last accepted one and was quarantined. `deviation`
+        /// saturates at 100%.
+        CircuitBreakerTripped {
+            feed: T::FeedId,
+            last: u128,
+            proposed: u128,
+            deviation: Perbill,
+        },
+        /// A quarantined value was confirmed and published
+        CircuitBreakerOverridden {
+            feed: T::FeedId,
+            value: u128,
+            confirmed_by: Option<T::AccountId>,
+        },
+        /// Price sources of a feed were replaced
+        FeedSourcesSet { feed: T::FeedId, count: u32 },
+        /// A feed was registered
+        FeedCreated { feed: T::FeedId },
+        /// Metadata of a feed was changed
+        FeedUpdated { feed: T::FeedId, state: FeedState },
+        /// A feed was retired
+        FeedDeprecated { feed: T::FeedId },
+        /// An operator submitted a batch of values, with the outcome of each in batch order
+        BatchSubmitted {
+            who: T::AccountId,
+            results: Vec<(T::FeedId, DispatchResult)>,
         },
     }
 
@@ -77,66 +646,1202 @@ pub mod pallet {
         InvalidValue,
         /// Source is empty
         InvalidSource,
+        /// Source is longer than `MaxSourceLen`
+        SourceTooLong,
+        /// Status cannot be submitted, e.g. `Stale` or an unknown failover level
+        InvalidStatus,
+        /// Sender is not an oracle operator
+        NotOperator,
+        /// Account is already an oracle operator
+        AlreadyOperator,
+        /// Operator set is full
+        TooManyOperators,
+        /// Operator already submitted to the open round
+        AlreadySubmitted,
+        /// Open round cannot take more submissions
+        RoundFull,
+        /// Feed has no value yet
+        NoValue,
+        /// Last value of the feed is older than allowed
+        StaleValue,
+        /// Feed has no quarantined value
+        NotQuarantined,
+        /// Quarantined value must be confirmed by an operator that did not report it
+        CannotConfirmOwnValue,
+        /// More price sources than failover levels
+        TooManySources,
+        /// Price source URL is empty
+        InvalidUrl,
+        /// Block already holds `MaxUnsignedPerBlock` unsigned submissions
+        TooManyUnsignedSubmissions,
+        /// Feed decimals exceed `MAX_DECIMALS`
+        InvalidDecimals,
+        /// Feed is not registered
+        UnknownFeed,
+        /// Feed is already registered
+        FeedAlreadyExists,
+        /// Feed is paused or deprecated
+        FeedNotActive,
+        /// Deprecated feeds cannot be changed
+        FeedDeprecated,
+        /// Base or quote symbol is empty
+        InvalidSymbol,
+        /// Minimum value is zero or above the maximum
+        InvalidBounds,
+        /// Value is outside the bounds of the feed
+        ValueOutOfBounds,
+        /// Observation time is ahead of the chain time
+        ObservationInFuture,
+        /// Observation time is older than the feed's `max_observation_age`
+        ObservationTooOld,
+        /// Observation time precedes that of the last published value
+        ObservationOutOfOrder,
+        /// Decimals of a feed cannot change once values were submitted to it
+        DecimalsLocked,
+        /// Signed payload is not for the current round of its feed
+        WrongRound,
+        /// Signed payload has no observation time
+        MissingObservation,
+        /// Witness data of a call is below the actual value
+        InvalidWitness,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            // The write accounts for clearing `UnsignedSubmissions` in `on_finalize`
+            Self::finalize_expired_rounds(now)
+                .saturating_add(Self::check_heartbeats_weight())
+                .saturating_add(T::DbWeight::get().writes(1))
+        }
+
+        fn on_finalize(_now: BlockNumberFor<T>) {
+            // Checked at the end of the block, when the timestamp of this block is set
+            Self::check_heartbeats();
+
+            // Cleared before the block is sealed, since the transaction pool validates against
+            // the state of the parent block without running `on_initialize`
+            UnsignedSubmissions::<T>::kill();
+        }
+
+        fn offchain_worker(now: BlockNumberFor<T>) {
+            Self::fetch_and_submit_prices(now);
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+            Self::do_try_state()
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Submit a new oracle value
-        #[pallet::weight(10_000)]
+        /// Submit a new oracle value for a feed.
+        ///
+        /// `observed_at` is the unix time in seconds the value was observed at its source,
+        /// defaulting to the current time.
+        #[pallet::weight(
+            T::WeightInfo::submit_oracle_value(source.len() as u32)
+                .saturating_add(T::OnNewValue::weight())
+        )]
         #[pallet::call_index(0)]
         pub fn submit_oracle_value(
             origin: OriginFor<T>,
+            feed: T::FeedId,
             value: u128,
             source: Vec<u8>,
-            status: Vec<u8>,
+            status: OracleStatus,
+            observed_at: Option<u64>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_submit(who, feed, value, source, status, observed_at)
+        }
+
+        /// Add an account to the operator set
+        #[pallet::weight(T::WeightInfo::add_operator())]
+        #[pallet::call_index(1)]
+        pub fn add_operator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            Operators::<T>::try_mutate(|operators| -> DispatchResult {
+                let pos = operators
+                    .binary_search(&who)
+                    .err()
+                    .ok_or(Error::<T>::AlreadyOperator)?;
+                operators
+                    .try_insert(pos, who.clone())
+                    .map_err(|_| Error::<T>::TooManyOperators)?;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::OperatorAdded { who });
+            Ok(())
+        }
+
+        /// Remove an account from the operator set
+        #[pallet::weight(T::WeightInfo::remove_operator())]
+        #[pallet::call_index(2)]
+        pub fn remove_operator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            Operators::<T>::try_mutate(|operators| -> DispatchResult {
+                let pos = operators
+                    .binary_search(&who)
+                    .map_err(|_| Error::<T>::NotOperator)?;
+                operators.remove(pos);
+                Ok(())
+            })?;
+            OperatorWeights::<T>::remove(&who);
+
+            Self::deposit_event(Event::OperatorRemoved { who });
+            Ok(())
+        }
+
+        /// Replace an operator account with a new one.
+        ///
+        /// Submissions of the old account to open rounds are kept under the new one, so that the
+        /// operator cannot report twice. `open_rounds` must be at least the number of open rounds.
+        #[pallet::weight(T::WeightInfo::rotate_operator(*open_rounds))]
+        #[pallet::call_index(3)]
+        pub fn rotate_operator(
+            origin: OriginFor<T>,
+            old: T::AccountId,
+            new: T::AccountId,
+            open_rounds: u32,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let rounds: Vec<(T::FeedId, Round<T>)> = Rounds::<T>::iter().collect();
+            ensure!(
+                rounds.len() as u32 <= open_rounds,
+                Error::<T>::InvalidWitness
+            );
+
+            Operators::<T>::try_mutate(|operators| -> DispatchResult {
+                let old_pos = operators
+                    .binary_search(&old)
+                    .map_err(|_| Error::<T>::NotOperator)?;
+                operators.remove(old_pos);
+                let new_pos = operators
+                    .binary_search(&new)
+                    .err()
+                    .ok_or(Error::<T>::AlreadyOperator)?;
+                operators
+                    .try_insert(new_pos, new.clone())
+                    .map_err(|_| Error::<T>::TooManyOperators)?;
+                Ok(())
+            })?;
+
+            OperatorWeights::<T>::insert(&new, OperatorWeights::<T>::take(&old));
+
+            for (feed, mut round) in rounds {
+                let Some(submission) = round.submissions.iter_mut().find(|s| s.operator == old)
+                else {
+                    continue;
+                };
+                submission.operator = new.clone();
+                Rounds::<T>::insert(feed, round);
+            }
+
+            Self::deposit_event(Event::OperatorRotated { old, new });
+            Ok(())
+        }
+
+        /// Set the weight of an operator in weighted median aggregation
+        #[pallet::weight(T::WeightInfo::set_operator_weight())]
+        #[pallet::call_index(4)]
+        pub fn set_operator_weight(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            weight: u32,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+
+            OperatorWeights::<T>::insert(&who, weight);
+
+            Self::deposit_event(Event::OperatorWeightSet { who, weight });
+            Ok(())
+        }
+
+        /// Set the configuration of a feed
+        #[pallet::weight(T::WeightInfo::set_feed_config())]
+        #[pallet::call_index(5)]
+        pub fn set_feed_config(
+            origin: OriginFor<T>,
+            feed: T::FeedId,
+            config: FeedConfig,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(Feeds::<T>::contains_key(feed), Error::<T>::UnknownFeed);
+            ensure!(config.decimals <= MAX_DECIMALS, Error::<T>::InvalidDecimals);
+            Self::ensure_decimals_kept(feed, &config)?;
+
+            FeedConfigs::<T>::insert(feed, &config);
+
+            Self::deposit_event(Event::FeedConfigSet { feed, config });
+            Ok(())
+        }
+
+        /// Publish the value held back by the circuit breaker.
+        ///
+        /// Requires the admin origin or an operator that did not report the value.
+        #[pallet::weight(
+            T::WeightInfo::confirm_quarantined().saturating_add(T::OnNewValue::weight())
+        )]
+        #[pallet::call_index(6)]
+        pub fn confirm_quarantined(origin: OriginFor<T>, feed: T::FeedId) -> DispatchResult {
+            let confirmed_by = match T::AdminOrigin::ensure_origin(origin.clone()) {
+                Ok(_) => None,
+                Err(_) => {
+                    let who = ensure_signed(origin)?;
+                    ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+                    Some(who)
+                }
+            };
+
+            let quarantined = Quarantine::<T>::get(feed).ok_or(Error::<T>::NotQuarantined)?;
+            if let Some(who) = &confirmed_by {
+                ensure!(
+                    !quarantined.reporters.contains(who),
+                    Error::<T>::CannotConfirmOwnValue
+                );
+            }
+            Quarantine::<T>::remove(feed);
+
+            let value = quarantined.value.value;
+            let old = Self::publish(feed, quarantined.value);
+            T::OnNewValue::on_new_value(&feed, old, value);
+
+            Self::deposit_event(Event::CircuitBreakerOverridden {
+                feed,
+                value,
+                confirmed_by,
+            });
+            Ok(())
+        }
+
+        /// Replace the price sources of a feed, highest priority first
+        #[pallet::weight(T::WeightInfo::set_feed_sources(sources.len() as u32))]
+        #[pallet::call_index(7)]
+        pub fn set_feed_sources(
+            origin: OriginFor<T>,
+            feed: T::FeedId,
+            sources: BoundedVec<PriceSourceOf<T>, T::MaxSources>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(Feeds::<T>::contains_key(feed), Error::<T>::UnknownFeed);
+            // Every fallback source must map onto a valid failover level
+            ensure!(
+                sources.len() <= T::MaxFailoverLevel::get() as usize + 1,
+                Error::<T>::TooManySources
+            );
+            for source in sources.iter() {
+                ensure!(!source.name.is_empty(), Error::<T>::InvalidSource);
+                ensure!(!source.url.is_empty(), Error::<T>::InvalidUrl);
+            }
+
+            let count = sources.len() as u32;
+            if sources.is_empty() {
+                FeedSources::<T>::remove(feed);
+            } else {
+                FeedSources::<T>::insert(feed, sources);
+            }
+
+            Self::deposit_event(Event::FeedSourcesSet { feed, count });
+            Ok(())
+        }
+
+        /// Submit a value signed by an operator key without paying fees.
+        ///
+        /// The signature is checked in `validate_unsigned`. The payload must be for the current
+        /// round of its feed and observed after the feed's last value, so it cannot be replayed.
+        #[pallet::weight(
+            T::WeightInfo::submit_oracle_value_unsigned_with_signed_payload(
+                payload.source.len() as u32,
+            )
+            .saturating_add(T::OnNewValue::weight())
+        )]
+        #[pallet::call_index(8)]
+        pub fn submit_oracle_value_unsigned_with_signed_payload(
+            origin: OriginFor<T>,
+            payload: OraclePayloadOf<T>,
+            _signature: T::Signature,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::validate_payload(&payload)?;
+
+            UnsignedSubmissions::<T>::try_mutate(|count| -> DispatchResult {
+                ensure!(
+                    *count < T::MaxUnsignedPerBlock::get(),
+                    Error::<T>::TooManyUnsignedSubmissions
+                );
+                count.saturating_inc();
+                Ok(())
+            })?;
+
+            let OraclePayload {
+                feed,
+                value,
+                source,
+                status,
+                observed_at,
+                public,
+                ..
+            } = payload;
+            Self::do_submit(
+                public.into_account(),
+                feed,
+                value,
+                source,
+                status,
+                observed_at,
+            )
+        }
+
+        /// Register a feed with its metadata and configuration
+        #[pallet::weight(T::WeightInfo::create_feed())]
+        #[pallet::call_index(9)]
+        pub fn create_feed(
+            origin: OriginFor<T>,
+            feed: T::FeedId,
+            info: FeedInfoOf<T>,
+            config: FeedConfig,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                !Feeds::<T>::contains_key(feed),
+                Error::<T>::FeedAlreadyExists
+            );
+            Self::validate_feed(&info, &config)?;
+
+            Feeds::<T>::insert(feed, info);
+            FeedConfigs::<T>::insert(feed, config);
+
+            Self::deposit_event(Event::FeedCreated { feed });
+            Ok(())
+        }
+
+        /// Replace the metadata and configuration of a feed, pausing or resuming it
+        #[pallet::weight(T::WeightInfo::update_feed())]
+        #[pallet::call_index(10)]
+        pub fn update_feed(
+            origin: OriginFor<T>,
+            feed: T::FeedId,
+            info: FeedInfoOf<T>,
+            config: FeedConfig,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let current = Feeds::<T>::get(feed).ok_or(Error::<T>::UnknownFeed)?;
+            ensure!(
+                current.state != FeedState::Deprecated,
+                Error::<T>::FeedDeprecated
+            );
+            Self::validate_feed(&info, &config)?;
+            Self::ensure_decimals_kept(feed, &config)?;
+
+            let state = info.state;
+            Feeds::<T>::insert(feed, info);
+            FeedConfigs::<T>::insert(feed, config);
+
+            Self::deposit_event(Event::FeedUpdated { feed, state });
+            Ok(())
+        }
+
+        /// Retire a feed for good.
+        ///
+        /// Its open round, quarantined value and price sources are dropped, while its published
+        /// values remain readable.
+        #[pallet::weight(T::WeightInfo::deprecate_feed())]
+        #[pallet::call_index(11)]
+        pub fn deprecate_feed(origin: OriginFor<T>, feed: T::FeedId) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            Feeds::<T>::try_mutate(feed, |maybe_info| -> DispatchResult {
+                let info = maybe_info.as_mut().ok_or(Error::<T>::UnknownFeed)?;
+                ensure!(
+                    info.state != FeedState::Deprecated,
+                    Error::<T>::FeedDeprecated
+                );
+                info.state = FeedState::Deprecated;
+                Ok(())
+            })?;
+            if let Some(round) = Rounds::<T>::take(feed) {
+                RoundDeadlines::<T>::remove(round.deadline, feed);
+            }
+            Quarantine::<T>::remove(feed);
+            FeedSources::<T>::remove(feed);
+            StaleFeeds::<T>::remove(feed);
+
+            Self::deposit_event(Event::FeedDeprecated { feed });
+            Ok(())
+        }
+
+        /// Submit values of several feeds reported by the same source and observed at the same
+        /// time.
+        ///
+        /// Every value is submitted like with `submit_oracle_value`. A value that is rejected
+        /// does not fail the batch; the outcome of each is reported in `BatchSubmitted`.
+        #[pallet::weight(
+            T::WeightInfo::submit_batch(values.len() as u32, source.len() as u32).saturating_add(
+                T::OnNewValue::weight().saturating_mul(values.len() as u64)
+            )
+        )]
+        #[pallet::call_index(12)]
+        pub fn submit_batch(
+            origin: OriginFor<T>,
+            values: BoundedVec<(T::FeedId, u128, OracleStatus), T::MaxBatchSize>,
+            source: Vec<u8>,
+            observed_at: Option<u64>,
         ) -> DispatchResult {
-            let _who = ensure_signed(origin)?;
+            let who = ensure_signed(origin)?;
+            ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+
+            let results = values
+                .into_iter()
+                .map(|(feed, value, status)| {
+                    let result = with_storage_layer(|| {
+                        let source = source.clone();
+                        Self::do_submit(who.clone(), feed, value, source, status, observed_at)
+                    });
+                    (feed, result)
+                })
+                .collect();
+
+            Self::deposit_event(Event::BatchSubmitted { who, results });
+            Ok(())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::submit_oracle_value_unsigned_with_signed_payload { payload, signature } =
+                call
+            else {
+                return InvalidTransaction::Call.into();
+            };
+
+            if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            let who = payload.public.clone().into_account();
+            if !Self::is_operator(&who) {
+                return InvalidTransaction::BadSigner.into();
+            }
+            if Self::validate_submission(
+                payload.feed,
+                payload.value,
+                payload.source.clone(),
+                &payload.status,
+            )
+            .is_err()
+            {
+                return InvalidTransaction::Call.into();
+            }
+            match Self::validate_payload(payload) {
+                Err(Error::<T>::MissingObservation) => return InvalidTransaction::Call.into(),
+                Err(_) => return InvalidTransaction::Stale.into(),
+                Ok(()) => {}
+            }
+            if Self::validate_observation(payload.feed, payload.observed_at).is_err() {
+                return InvalidTransaction::Stale.into();
+            }
+            if UnsignedSubmissions::<T>::get() >= T::MaxUnsignedPerBlock::get() {
+                return InvalidTransaction::ExhaustsResources.into();
+            }
+
+            // A payload is valid for `UnsignedLongevity` blocks after it was signed
+            let now = <frame_system::Pallet<T>>::block_number();
+            if payload.block_number > now {
+                return InvalidTransaction::Future.into();
+            }
+            let expires = payload
+                .block_number
+                .saturating_add(T::UnsignedLongevity::get());
+            if expires <= now {
+                return InvalidTransaction::Stale.into();
+            }
+
+            let round = Rounds::<T>::get(payload.feed);
+            if round.map_or(false, |r| r.submissions.iter().any(|s| s.operator == who)) {
+                return InvalidTransaction::Stale.into();
+            }
+
+            // Fresher prices are included first
+            let age: u64 = now.saturating_sub(payload.block_number).saturated_into();
+            ValidTransaction::with_tag_prefix("RoracleUnsigned")
+                .priority(T::UnsignedPriority::get().saturating_sub(age))
+                .and_provides((payload.feed, who, payload.round))
+                .longevity(expires.saturating_sub(now).saturated_into::<u64>())
+                .propagate(true)
+                .build()
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Check whether an account is an oracle operator
+        pub fn is_operator(who: &T::AccountId) -> bool {
+            Operators::<T>::get().binary_search(who).is_ok()
+        }
+
+        /// Current unix time in seconds
+        pub(crate) fn now() -> u64 {
+            T::TimeProvider::now().as_secs()
+        }
+
+        /// Last value of a feed, failing if it is older than `max_age` seconds
+        pub fn get_fresh_value(
+            feed: T::FeedId,
+            max_age: u64,
+        ) -> Result<OracleValueOf<T>, DispatchError> {
+            let value = LastValue::<T>::get(feed).ok_or(Error::<T>::NoValue)?;
+            ensure!(
+                Self::now().saturating_sub(value.updated_at) <= max_age,
+                Error::<T>::StaleValue
+            );
+            Ok(value)
+        }
+
+        /// Status of the last value of a feed, reporting `Stale` once it missed its heartbeat
+        pub fn feed_status(feed: T::FeedId) -> Option<OracleStatus> {
+            let value = LastValue::<T>::get(feed)?;
+            if StaleFeeds::<T>::contains_key(feed) {
+                Some(OracleStatus::Stale)
+            } else {
+                Some(value.status)
+            }
+        }
+
+        /// Retained history entries of a feed with an index in `from..to`
+        pub fn history_range(feed: T::FeedId, from: u64, to: u64) -> Vec<(u64, OracleValueOf<T>)> {
+            let from = from.max(HistoryStart::<T>::get(feed));
+            let to = to.min(HistoryIndex::<T>::get(feed));
+            (from..to)
+                .filter_map(|index| History::<T>::get(feed, index).map(|value| (index, value)))
+                .collect()
+        }
+
+        /// Retained history entries of a feed updated between `from` and `to` (inclusive)
+        pub fn history_by_time(
+            feed: T::FeedId,
+            from: u64,
+            to: u64,
+        ) -> Vec<(u64, OracleValueOf<T>)> {
+            let end = HistoryIndex::<T>::get(feed);
+
+            // History timestamps never decrease, so binary search the first entry at or after `from`
+            let mut low = HistoryStart::<T>::get(feed);
+            let mut high = end;
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match History::<T>::get(feed, mid) {
+                    Some(value) if value.updated_at < from => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+
+            (low..end)
+                .map_while(|index| {
+                    History::<T>::get(feed, index)
+                        .filter(|value| value.updated_at <= to)
+                        .map(|value| (index, value))
+                })
+                .collect()
+        }
+
+        /// Feeds that have a published value
+        pub fn feeds() -> Vec<T::FeedId> {
+            LastValue::<T>::iter_keys().collect()
+        }
+
+        /// Whether a feed missed its heartbeat
+        pub fn is_stale(feed: T::FeedId) -> bool {
+            StaleFeeds::<T>::contains_key(feed)
+        }
+
+        /// Check the consistency of stored feed data:
+        ///
+        /// - `History` holds exactly the entries `HistoryStart..HistoryIndex` of every feed
+        /// - `LastValue` equals the most recent history entry
+        /// - history timestamps never decrease
+        /// - feed configurations and sources only exist for registered feeds
+        /// - the operator set is sorted and free of duplicates
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), TryRuntimeError> {
+            for (feed, end) in HistoryIndex::<T>::iter() {
+                let start = HistoryStart::<T>::get(feed);
+                ensure!(start <= end, "HistoryStart is past HistoryIndex");
+                ensure!(
+                    History::<T>::iter_key_prefix(feed).count() as u64 == end - start,
+                    "number of History entries does not match HistoryIndex"
+                );
+
+                let mut last: Option<OracleValueOf<T>> = None;
+                for index in start..end {
+                    let value = History::<T>::get(feed, index).ok_or("History entry is missing")?;
+                    if let Some(previous) = &last {
+                        ensure!(
+                            previous.updated_at <= value.updated_at,
+                            "History timestamps are not monotonic"
+                        );
+                        ensure!(
+                            previous.observed_at <= value.observed_at,
+                            "History observation times are not monotonic"
+                        );
+                    }
+                    last = Some(value);
+                }
+
+                if let Some(last) = last {
+                    ensure!(
+                        LastValue::<T>::get(feed) == Some(last),
+                        "LastValue differs from the latest History entry"
+                    );
+                }
+            }
+
+            for feed in History::<T>::iter_keys().map(|(feed, _)| feed) {
+                ensure!(
+                    HistoryIndex::<T>::contains_key(feed),
+                    "History of a feed without index"
+                );
+            }
+            for feed in LastValue::<T>::iter_keys() {
+                ensure!(
+                    HistoryIndex::<T>::contains_key(feed),
+                    "LastValue of a feed without history"
+                );
+            }
+
+            for feed in FeedConfigs::<T>::iter_keys() {
+                ensure!(
+                    Feeds::<T>::contains_key(feed),
+                    "FeedConfigs of an unregistered feed"
+                );
+            }
+
+            for (feed, round) in Rounds::<T>::iter() {
+                ensure!(
+                    RoundDeadlines::<T>::contains_key(round.deadline, feed),
+                    "open round is not indexed at its deadline"
+                );
+            }
+            for (deadline, feed, _) in RoundDeadlines::<T>::iter() {
+                ensure!(
+                    Rounds::<T>::get(feed).map_or(false, |round| round.deadline == deadline),
+                    "RoundDeadlines entry without an open round"
+                );
+            }
+            if let Some(cursor) = RoundDeadlineCursor::<T>::get() {
+                ensure!(
+                    RoundDeadlines::<T>::iter_keys().all(|(deadline, _)| deadline >= cursor),
+                    "RoundDeadlines entry before the RoundDeadlineCursor"
+                );
+            }
+            for feed in FeedSources::<T>::iter_keys() {
+                ensure!(
+                    Feeds::<T>::contains_key(feed),
+                    "FeedSources of an unregistered feed"
+                );
+            }
+
+            for ((_, interval, _), candle) in Candles::<T>::iter() {
+                ensure!(
+                    interval > 0 && candle.start % interval == 0,
+                    "Candle does not start at its interval"
+                );
+                ensure!(
+                    candle.low <= candle.open.min(candle.close)
+                        && candle.open.max(candle.close) <= candle.high,
+                    "Candle open or close outside of its low and high"
+                );
+            }
+
+            let operators = Operators::<T>::get();
+            ensure!(
+                operators.windows(2).all(|pair| pair[0] < pair[1]),
+                "Operators are not sorted or contain duplicates"
+            );
+
+            Ok(())
+        }
+
+        /// Finalize the open rounds whose deadline has passed, oldest deadline first.
+        ///
+        /// At most `MaxRoundsPerBlock` rounds are closed and as many deadline blocks visited per
+        /// block. Rounds beyond that stay indexed, and the `RoundDeadlineCursor` resumes at their
+        /// deadline in the next block.
+        pub(crate) fn finalize_expired_rounds(now: BlockNumberFor<T>) -> Weight {
+            let max_rounds = T::MaxRoundsPerBlock::get();
+            let cursor = RoundDeadlineCursor::<T>::get();
+            let mut block = cursor.unwrap_or(now);
+            let mut visited = 0u32;
+            let mut closed = 0u32;
+
+            let next_cursor = loop {
+                if block > now {
+                    break None;
+                }
+                if visited >= max_rounds || closed >= max_rounds {
+                    break Some(block);
+                }
+                visited.saturating_inc();
 
-            // Validate inputs
+                let remaining = (max_rounds - closed) as usize;
+                let mut expired: Vec<T::FeedId> = RoundDeadlines::<T>::iter_key_prefix(block)
+                    .take(remaining + 1)
+                    .collect();
+                let left_open = expired.len() > remaining;
+                expired.truncate(remaining);
+
+                closed.saturating_accrue(expired.len() as u32);
+                for feed in expired {
+                    Self::finalize_round(feed);
+                }
+                if left_open {
+                    break Some(block);
+                }
+                block.saturating_inc();
+            };
+
+            if next_cursor != cursor {
+                RoundDeadlineCursor::<T>::set(next_cursor);
+            }
+
+            // The benchmark covers visiting one deadline block
+            T::WeightInfo::finalize_expired_rounds(closed)
+                .saturating_add(T::OnNewValue::weight().saturating_mul(closed.into()))
+                .saturating_add(T::DbWeight::get().reads(visited.into()))
+        }
+
+        /// Mark feeds whose last value is older than their heartbeat as stale.
+        ///
+        /// At most `MaxHeartbeatChecks` feeds are checked per block, continuing after the
+        /// `HeartbeatCursor` so that every feed is checked in turn.
+        fn check_heartbeats() {
+            let now = Self::now();
+            let mut configs = match HeartbeatCursor::<T>::get() {
+                Some(cursor) => {
+                    FeedConfigs::<T>::iter_from(FeedConfigs::<T>::hashed_key_for(cursor))
+                }
+                None => FeedConfigs::<T>::iter(),
+            };
+
+            let mut last = None;
+            for (feed, config) in configs.by_ref().take(T::MaxHeartbeatChecks::get() as usize) {
+                last = Some(feed);
+                let Some(heartbeat) = config.heartbeat else {
+                    continue;
+                };
+                // Paused and deprecated feeds are not expected to receive values
+                if !Self::is_active(feed) || StaleFeeds::<T>::contains_key(feed) {
+                    continue;
+                }
+                let Some(value) = LastValue::<T>::get(feed) else {
+                    continue;
+                };
+
+                if now.saturating_sub(value.updated_at) > heartbeat {
+                    StaleFeeds::<T>::insert(feed, value.updated_at);
+                    Self::deposit_event(Event::FeedStale {
+                        feed,
+                        last_update: value.updated_at,
+                    });
+                }
+            }
+
+            match (last, configs.next()) {
+                (Some(feed), Some(_)) => HeartbeatCursor::<T>::put(feed),
+                _ => HeartbeatCursor::<T>::kill(),
+            }
+        }
+
+        /// Worst case weight of `check_heartbeats`, reserved in `on_initialize`
+        fn check_heartbeats_weight() -> Weight {
+            let checks = T::MaxHeartbeatChecks::get() as u64;
+            // Each check reads the config, the feed, the stale marker and the last value, and may
+            // mark the feed stale; the cursor and the feed after the last check are read on top
+            T::DbWeight::get().reads_writes(
+                checks.saturating_mul(4).saturating_add(2),
+                checks.saturating_add(1),
+            )
+        }
+
+        /// Check whether a status may be reported by a submitter
+        pub fn is_valid_status(status: &OracleStatus) -> bool {
+            match status {
+                OracleStatus::Failover(level) => (1..=T::MaxFailoverLevel::get()).contains(level),
+                OracleStatus::Stale => false,
+                _ => true,
+            }
+        }
+
+        /// Number of decimals of a feed's values
+        pub fn decimals(feed: T::FeedId) -> u8 {
+            FeedConfigs::<T>::get(feed).unwrap_or_default().decimals
+        }
+
+        /// Last value of a feed as a fixed point number, if it has one that fits
+        pub fn fixed_value(feed: T::FeedId) -> Option<FixedU128> {
+            let value = LastValue::<T>::get(feed)?;
+            fixed::to_fixed(value.value, Self::decimals(feed))
+        }
+
+        /// Whether a feed is registered and accepts submissions
+        pub fn is_active(feed: T::FeedId) -> bool {
+            Feeds::<T>::get(feed).map_or(false, |info| info.state == FeedState::Active)
+        }
+
+        /// Validate the metadata and configuration of a feed
+        fn validate_feed(info: &FeedInfoOf<T>, config: &FeedConfig) -> Result<(), Error<T>> {
+            ensure!(
+                !info.base.is_empty() && !info.quote.is_empty(),
+                Error::<T>::InvalidSymbol
+            );
+            ensure!(
+                info.min_value > 0 && info.min_value <= info.max_value,
+                Error::<T>::InvalidBounds
+            );
+            // Feeds are only deprecated through `deprecate_feed`
+            ensure!(
+                info.state != FeedState::Deprecated,
+                Error::<T>::FeedDeprecated
+            );
+            ensure!(config.decimals <= MAX_DECIMALS, Error::<T>::InvalidDecimals);
+            Ok(())
+        }
+
+        /// Reject a change of decimals of a feed with a value or an open round, since its stored
+        /// values, submissions, accumulators and candles would be read with the new decimals
+        fn ensure_decimals_kept(feed: T::FeedId, config: &FeedConfig) -> Result<(), Error<T>> {
+            if LastValue::<T>::contains_key(feed) || Rounds::<T>::contains_key(feed) {
+                ensure!(
+                    config.decimals == Self::decimals(feed),
+                    Error::<T>::DecimalsLocked
+                );
+            }
+            Ok(())
+        }
+
+        /// Validate the inputs of a submission
+        fn validate_submission(
+            feed: T::FeedId,
+            value: u128,
+            source: Vec<u8>,
+            status: &OracleStatus,
+        ) -> Result<SourceOf<T>, Error<T>> {
+            let info = Feeds::<T>::get(feed).ok_or(Error::<T>::UnknownFeed)?;
+            ensure!(info.state == FeedState::Active, Error::<T>::FeedNotActive);
             ensure!(value > 0, Error::<T>::InvalidValue);
+            ensure!(
+                (info.min_value..=info.max_value).contains(&value),
+                Error::<T>::ValueOutOfBounds
+            );
             ensure!(!source.is_empty(), Error::<T>::InvalidSource);
+            let source: SourceOf<T> = source.try_into().map_err(|_| Error::<T>::SourceTooLong)?;
+            ensure!(Self::is_valid_status(status), Error::<T>::InvalidStatus);
+            Ok(source)
+        }
+
+        /// Observation time of a submission, defaulting to now, if it is not ahead of the chain,
+        /// within the feed's `max_observation_age` and not before the last published value
+        fn validate_observation(
+            feed: T::FeedId,
+            observed_at: Option<u64>,
+        ) -> Result<u64, Error<T>> {
+            let now = Self::now();
+            let observed_at = observed_at.unwrap_or(now);
+            ensure!(observed_at <= now, Error::<T>::ObservationInFuture);
+            let max_age = FeedConfigs::<T>::get(feed).and_then(|config| config.max_observation_age);
+            if let Some(max_age) = max_age {
+                ensure!(now - observed_at <= max_age, Error::<T>::ObservationTooOld);
+            }
+            if let Some(last) = LastValue::<T>::get(feed) {
+                ensure!(
+                    observed_at >= last.observed_at,
+                    Error::<T>::ObservationOutOfOrder
+                );
+            }
+            Ok(observed_at)
+        }
+
+        /// Id of the open round of a feed, or of the round the next submission opens
+        pub fn current_round(feed: T::FeedId) -> u64 {
+            Rounds::<T>::get(feed).map_or_else(|| NextRoundId::<T>::get(feed), |round| round.id)
+        }
+
+        /// Check that a signed payload can only be submitted once: it must be for the current
+        /// round of its feed and observed after the feed's last value
+        fn validate_payload(payload: &OraclePayloadOf<T>) -> Result<(), Error<T>> {
+            ensure!(
+                payload.round == Self::current_round(payload.feed),
+                Error::<T>::WrongRound
+            );
+            let observed_at = payload.observed_at.ok_or(Error::<T>::MissingObservation)?;
+            if let Some(last) = LastValue::<T>::get(payload.feed) {
+                ensure!(
+                    observed_at > last.observed_at,
+                    Error::<T>::ObservationOutOfOrder
+                );
+            }
+            Ok(())
+        }
+
+        /// Add an operator submission to the open round of a feed, finalizing it on quorum
+        pub(crate) fn do_submit(
+            who: T::AccountId,
+            feed: T::FeedId,
+            value: u128,
+            source: Vec<u8>,
+            status: OracleStatus,
+            observed_at: Option<u64>,
+        ) -> DispatchResult {
+            ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+            let source = Self::validate_submission(feed, value, source, &status)?;
+            let observed_at = Self::validate_observation(feed, observed_at)?;
+
+            let now = <frame_system::Pallet<T>>::block_number();
+            // Rounds close in the `on_initialize` of their deadline at the earliest, which must be
+            // a later block
+            let deadline = now.saturating_add(T::RoundTimeout::get().max(One::one()));
+            let (round_id, quorum_reached, opened) = Rounds::<T>::try_mutate(
+                feed,
+                |maybe_round| -> Result<(u64, bool, bool), DispatchError> {
+                    let opened = maybe_round.is_none();
+                    let round = maybe_round.get_or_insert_with(|| Round {
+                        id: NextRoundId::<T>::mutate(feed, |id| {
+                            let current = *id;
+                            id.saturating_inc();
+                            current
+                        }),
+                        deadline,
+                        submissions: Default::default(),
+                    });
+
+                    ensure!(
+                        !round.submissions.iter().any(|s| s.operator == who),
+                        Error::<T>::AlreadySubmitted
+                    );
+                    round
+                        .submissions
+                        .try_push(Submission {
+                            operator: who.clone(),
+                            value,
+                            source,
+                            status,
+                            observed_at,
+                        })
+                        .map_err(|_| Error::<T>::RoundFull)?;
+
+                    let quorum_reached = round.submissions.len() as u32 >= T::MinQuorum::get();
+                    Ok((round.id, quorum_reached, opened))
+                },
+            )?;
+
+            if opened {
+                RoundDeadlines::<T>::insert(deadline, feed, ());
+            }
+            Self::deposit_event(Event::SubmissionReceived {
+                feed,
+                round: round_id,
+                who,
+                value,
+            });
+
+            if quorum_reached {
+                Self::finalize_round(feed);
+            }
+
+            Ok(())
+        }
+
+        /// Append a value to the history of a feed, dropping entries beyond `MaxHistory`
+        fn append_history(feed: T::FeedId, oracle_value: &OracleValueOf<T>) {
+            let index = HistoryIndex::<T>::get(feed);
+            History::<T>::insert(feed, index, oracle_value);
+            let next = index.saturating_add(1);
+            HistoryIndex::<T>::insert(feed, next);
+
+            // Removing up to two entries per insert lets the history shrink back
+            // to `MaxHistory` after the bound was lowered, while keeping the
+            // cost of a single insert bounded
+            let mut start = HistoryStart::<T>::get(feed);
+            let mut removed = 0u64;
+            while removed < 2 && next.saturating_sub(start) > T::MaxHistory::get() as u64 {
+                History::<T>::remove(feed, start);
+                start.saturating_inc();
+                removed.saturating_inc();
+            }
+
+            if removed > 0 {
+                HistoryStart::<T>::insert(feed, start);
+                Self::deposit_event(Event::HistoryPruned {
+                    feed,
+                    removed,
+                    oldest: start,
+                });
+            }
+        }
+
+        /// Close the open round of a feed and publish its aggregated value
+        pub(crate) fn finalize_round(feed: T::FeedId) {
+            let Some(round) = Rounds::<T>::take(feed) else {
+                return;
+            };
+            RoundDeadlines::<T>::remove(round.deadline, feed);
+
+            let samples: Vec<(u128, u32)> = round
+                .submissions
+                .iter()
+                .map(|s| (s.value, OperatorWeights::<T>::get(&s.operator)))
+                .collect();
+            let Some(value) = aggregation::aggregate(T::Aggregation::get(), &samples) else {
+                return;
+            };
+
+            // Report the source, status and observation time of the submission closest to the
+            // aggregate
+            let Some(closest) = round
+                .submissions
+                .iter()
+                .min_by_key(|s| s.value.abs_diff(value))
+            else {
+                return;
+            };
+
+            // A value newer than the round's was published while it was open
+            let observed_at = closest.observed_at;
+            if LastValue::<T>::get(feed).map_or(false, |last| last.observed_at > observed_at) {
+                Self::deposit_event(Event::RoundOutOfOrder {
+                    feed,
+                    round: round.id,
+                    observed_at,
+                });
+                return;
+            }
 
             // Get current timestamp
-            let updated_at = T::TimeProvider::now().as_secs();
+            let updated_at = Self::now();
+
+            let values: Vec<u128> = samples.iter().map(|(value, _)| *value).collect();
+            let confidence = aggregation::median_absolute_deviation(&values, value);
 
             // Create oracle value
-            let oracle_value = OracleValue {
+            let oracle_value: OracleValueOf<T> = OracleValue {
                 value,
-                source: source.clone(),
+                source: closest.source.clone(),
                 updated_at,
-                status: status.clone(),
+                status: closest.status,
+                confidence,
+                observed_at,
             };
 
+            Self::deposit_event(Event::RoundFinalized {
+                feed,
+                round: round.id,
+                value,
+                participants: round.submissions.len() as u32,
+            });
+
+            if let Some((last, deviation)) = Self::excessive_deviation(feed, value, updated_at) {
+                let reporters: Vec<T::AccountId> = round
+                    .submissions
+                    .iter()
+                    .map(|s| s.operator.clone())
+                    .collect();
+                Quarantine::<T>::insert(
+                    feed,
+                    QuarantinedValue {
+                        value: oracle_value,
+                        deviation,
+                        reporters: BoundedVec::truncate_from(reporters),
+                    },
+                );
+                Self::deposit_event(Event::CircuitBreakerTripped {
+                    feed,
+                    last,
+                    proposed: value,
+                    deviation,
+                });
+                return;
+            }
+
+            // An accepted value supersedes anything held back
+            Quarantine::<T>::remove(feed);
+            let old = Self::publish(feed, oracle_value);
+            T::OnNewValue::on_new_value(&feed, old, value);
+        }
+
+        /// Last accepted value and deviation if `value` moves further than the feed allows
+        fn excessive_deviation(feed: T::FeedId, value: u128, now: u64) -> Option<(u128, Perbill)> {
+            let config = FeedConfigs::<T>::get(feed)?;
+            let max_deviation = config.max_deviation?;
+            let last = LastValue::<T>::get(feed)?;
+            if now.saturating_sub(last.updated_at) > config.deviation_window {
+                return None;
+            }
+
+            // Compared in absolute terms, since the ratio saturates at 100% for larger moves
+            let diff = value.abs_diff(last.value);
+            if diff <= max_deviation.mul_floor(last.value) {
+                return None;
+            }
+            Some((last.value, Perbill::from_rational(diff, last.value)))
+        }
+
+        /// Store a value as the latest of its feed and record it in history, returning the value
+        /// it replaces
+        fn publish(feed: T::FeedId, oracle_value: OracleValueOf<T>) -> Option<u128> {
             // Update last value
-            LastValue::<T>::put(&oracle_value);
+            let old = LastValue::<T>::get(feed).map(|last| last.value);
+            LastValue::<T>::insert(feed, &oracle_value);
 
             // Add to history
-            let index = HistoryIndex::<T>::get();
-            History::<T>::insert(index, &oracle_value);
-            HistoryIndex::<T>::put(index.saturating_add(1));
+            Self::append_history(feed, &oracle_value);
+            Self::accumulate_price(feed, oracle_value.value, oracle_value.updated_at);
+            Self::record_candles(feed, oracle_value.value, oracle_value.updated_at);
 
             // Emit event
             Self::deposit_event(Event::ValueUpdated {
-                value,
-                source,
-                updated_at,
-                status,
+                feed,
+                value: oracle_value.value,
+                source: oracle_value.source,
+                updated_at: oracle_value.updated_at,
+                status: oracle_value.status,
+                confidence: oracle_value.confidence,
+                observed_at: oracle_value.observed_at,
             });
 
-            Ok(())
+            if StaleFeeds::<T>::take(feed).is_some() {
+                Self::deposit_event(Event::FeedRecovered { feed });
+            }
+            old
         }
     }
 
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
-        pub _phantom: PhantomData<T>,
+        /// Initial oracle operators
+        pub operators: Vec<T::AccountId>,
+        /// Initial feeds with their metadata and configuration
+        pub feeds: Vec<(T::FeedId, FeedInfoOf<T>, FeedConfig)>,
+        /// Values published for feeds before any operator reports, e.g. for test networks
+        pub values: Vec<(T::FeedId, u128)>,
+        /// Unix time in seconds `values` are recorded as published and observed at, since the
+        /// chain has no time before its first block
+        pub values_published_at: u64,
     }
 
     #[cfg(feature = "std")]
     impl<T: Config> Default for GenesisConfig<T> {
         fn default() -> Self {
             Self {
-                _phantom: Default::default(),
+                operators: Vec::new(),
+                feeds: Vec::new(),
+                values: Vec::new(),
+                values_published_at: 0,
             }
         }
     }
@@ -144,11 +1849,51 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
-            HistoryIndex::<T>::put(0u64);
+            let mut operators = self.operators.clone();
+            operators.sort();
+            operators.dedup();
+            let operators: BoundedVec<T::AccountId, T::MaxOperators> =
+                operators.try_into().expect("too many genesis operators");
+            Operators::<T>::put(operators);
+
+            for (feed, info, config) in &self.feeds {
+                assert!(!Feeds::<T>::contains_key(feed), "duplicate genesis feed");
+                Pallet::<T>::validate_feed(info, config).expect("invalid genesis feed");
+                Feeds::<T>::insert(feed, info);
+                FeedConfigs::<T>::insert(feed, config);
+            }
+
+            for (feed, value) in &self.values {
+                assert!(*value > 0, "genesis values must be non-zero");
+                assert!(
+                    self.values_published_at > 0,
+                    "genesis values need a publication time"
+                );
+                let info = Feeds::<T>::get(feed).expect("genesis value of an unregistered feed");
+                assert!(
+                    (info.min_value..=info.max_value).contains(value),
+                    "genesis value is outside the bounds of its feed"
+                );
+                assert!(
+                    !LastValue::<T>::contains_key(feed),
+                    "duplicate genesis value"
+                );
+                // `OnNewValue` handlers are not notified of genesis values
+                Pallet::<T>::publish(
+                    *feed,
+                    OracleValue {
+                        value: *value,
+                        source: BoundedVec::truncate_from(b"genesis".to_vec()),
+                        updated_at: self.values_published_at,
+                        status: OracleStatus::Manual,
+                        confidence: 0,
+                        observed_at: self.values_published_at,
+                    },
+                );
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests;
-
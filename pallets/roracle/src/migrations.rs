@@ -0,0 +1,781 @@
+//! Storage migrations for the oracle pallet
+//!
+//! Every change to the layout of a stored type bumps `STORAGE_VERSION` and adds a module here
+//! holding a frozen copy of the previous layout and a migration that only runs from the version
+//! before it, so the runtime can list migrations in order and skip those already applied.
+//! Migrations whose target layout changed since write through storage aliases of that layout.
+
+use super::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade, weights::Weight};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::{traits::Saturating, Perbill};
+use sp_std::marker::PhantomData;
+
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+/// Migrate from the single unnamed feed with unbounded `source` bytes and free-form `status`
+/// bytes to feed-keyed storage with a bounded source and a typed [`OracleStatus`].
+pub mod v1 {
+    use super::{v4::v3, *};
+    use sp_std::prelude::*;
+
+    /// Layout of the storage items before version 1, holding the values of a single feed.
+    pub mod v0 {
+        use super::*;
+        use codec::{Decode, Encode};
+
+        #[derive(Encode, Decode)]
+        pub struct OracleValue {
+            pub value: u128,
+            pub source: Vec<u8>,
+            pub updated_at: u64,
+            pub status: Vec<u8>,
+        }
+
+        #[frame_support::storage_alias]
+        pub type LastValue<T: Config> = StorageValue<Pallet<T>, OracleValue, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type History<T: Config> =
+            StorageMap<Pallet<T>, Blake2_128Concat, u64, OracleValue, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type HistoryIndex<T: Config> = StorageValue<Pallet<T>, u64, ValueQuery>;
+    }
+
+    /// Map a free-form status onto the closest typed status
+    pub fn status_from_bytes(status: &[u8]) -> OracleStatus {
+        match status {
+            b"" | b"ok" | b"success" | b"primary" => OracleStatus::Primary,
+            b"fallback" | b"failover" | b"warning" => OracleStatus::Failover(1),
+            b"manual" => OracleStatus::Manual,
+            b"stale" => OracleStatus::Stale,
+            _ => OracleStatus::Degraded,
+        }
+    }
+
+    fn translate_value<T: Config>(old: v0::OracleValue) -> v3::OracleValue<T::MaxSourceLen> {
+        v3::OracleValue {
+            value: old.value,
+            source: BoundedVec::truncate_from(old.source),
+            updated_at: old.updated_at,
+            status: status_from_bytes(&old.status),
+        }
+    }
+
+    /// Moves the last value, history and history index of the single feed to the feed `Feed`,
+    /// truncating sources that exceed `MaxSourceLen` and typing statuses.
+    pub struct MigrateToV1<T, Feed>(PhantomData<(T, Feed)>);
+
+    impl<T: Config, Feed: Get<T::FeedId>> OnRuntimeUpgrade for MigrateToV1<T, Feed> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version >= 1 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v1 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let feed = Feed::get();
+            let mut translated = 0u64;
+
+            // The feed-keyed items are stored under the prefixes of the old ones, so every old
+            // entry is taken before any is written back
+            let last_value = v0::LastValue::<T>::take();
+            let history = v0::History::<T>::drain().collect::<Vec<_>>();
+            let index = v0::HistoryIndex::<T>::take();
+
+            if let Some(old) = last_value {
+                translated.saturating_inc();
+                v3::LastValue::<T>::insert(feed, translate_value::<T>(old));
+            }
+            for (index, old) in history {
+                translated.saturating_inc();
+                v3::History::<T>::insert(feed, index, translate_value::<T>(old));
+            }
+            if index > 0 {
+                HistoryIndex::<T>::insert(feed, index);
+            }
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "migrated {} entries to v1", translated);
+
+            // Every entry is removed and written back, plus the history index and the version
+            T::DbWeight::get().reads_writes(
+                translated.saturating_add(2),
+                translated.saturating_mul(2).saturating_add(3),
+            )
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            // Keys of the old layout cannot be told apart from those of the new one
+            if Pallet::<T>::on_chain_storage_version() >= 1 {
+                return Ok(None::<(bool, u64, u64)>.encode());
+            }
+            let state = (
+                v0::LastValue::<T>::exists(),
+                v0::History::<T>::iter_keys().count() as u64,
+                v0::HistoryIndex::<T>::get(),
+            );
+            Ok(Some(state).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let state: Option<(bool, u64, u64)> =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+            let Some((had_last_value, history, index)) = state else {
+                return Ok(());
+            };
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 1,
+                "storage version was not updated to v1"
+            );
+            ensure!(
+                !v0::LastValue::<T>::exists(),
+                "LastValue of the old layout was not removed"
+            );
+
+            let feed = Feed::get();
+            ensure!(
+                v3::LastValue::<T>::get(feed).is_some() == had_last_value,
+                "LastValue was not moved to the feed"
+            );
+            // Entries that fail to decode are skipped when iterating
+            ensure!(
+                v3::History::<T>::iter_prefix(feed).count() as u64 == history,
+                "History entries were not moved to the feed"
+            );
+            ensure!(
+                HistoryIndex::<T>::get(feed) == index,
+                "HistoryIndex was not moved to the feed"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Add the number of decimals to feed configurations.
+pub mod v2 {
+    use super::{v5::v4, *};
+    use sp_std::prelude::*;
+
+    /// Layout of the storage items before version 2.
+    pub mod v1 {
+        use super::*;
+        use codec::{Decode, Encode};
+
+        #[derive(Encode, Decode)]
+        pub struct FeedConfig {
+            pub heartbeat: Option<u64>,
+            pub max_deviation: Option<Perbill>,
+            pub deviation_window: u64,
+        }
+    }
+
+    /// Gives every configured feed the decimals the offchain worker reports values with.
+    pub struct MigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 1 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v2 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+
+            v4::FeedConfigs::<T>::translate::<v1::FeedConfig, _>(|_, old| {
+                translated.saturating_inc();
+                Some(v4::FeedConfig {
+                    heartbeat: old.heartbeat,
+                    max_deviation: old.max_deviation,
+                    deviation_window: old.deviation_window,
+                    decimals: crate::offchain::PRICE_DECIMALS as u8,
+                })
+            });
+
+            StorageVersion::new(2).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "migrated {} feed configs to v2", translated);
+
+            T::DbWeight::get()
+                .reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            Ok((FeedConfigs::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let configs: u64 =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 2,
+                "storage version was not updated to v2"
+            );
+            ensure!(
+                v4::FeedConfigs::<T>::iter().count() as u64 == configs,
+                "FeedConfigs entries do not decode after the migration"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Register every feed that has data, since submissions to unregistered feeds are rejected.
+pub mod v3 {
+    use super::*;
+    use sp_std::{collections::btree_set::BTreeSet, prelude::*};
+
+    /// Registers feeds with a value, configuration, price sources or open round as active, with
+    /// empty metadata and unrestricted bounds for the admin origin to fill in with `update_feed`.
+    pub struct MigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> MigrateToV3<T> {
+        fn existing_feeds() -> BTreeSet<T::FeedId> {
+            LastValue::<T>::iter_keys()
+                .chain(FeedConfigs::<T>::iter_keys())
+                .chain(FeedSources::<T>::iter_keys())
+                .chain(Rounds::<T>::iter_keys())
+                .collect()
+        }
+    }
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 2 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v3 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let feeds = Self::existing_feeds();
+            let mut registered = 0u64;
+            for feed in feeds.iter() {
+                if Feeds::<T>::contains_key(feed) {
+                    continue;
+                }
+                Feeds::<T>::insert(
+                    feed,
+                    FeedInfo {
+                        base: Default::default(),
+                        quote: Default::default(),
+                        description: Default::default(),
+                        min_value: 1,
+                        max_value: u128::MAX,
+                        state: FeedState::Active,
+                    },
+                );
+                registered.saturating_inc();
+            }
+
+            StorageVersion::new(3).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "registered {} feeds in v3", registered);
+
+            // Keys of four maps plus a registry lookup per feed
+            let reads = (feeds.len() as u64).saturating_mul(5).saturating_add(1);
+            T::DbWeight::get().reads_writes(reads, registered.saturating_add(1))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            Ok(Self::existing_feeds()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let feeds: Vec<T::FeedId> =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 3,
+                "storage version was not updated to v3"
+            );
+            for feed in feeds {
+                ensure!(
+                    Feeds::<T>::contains_key(feed),
+                    "feed with data was not registered"
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Add the spread of the aggregated submissions to oracle values.
+pub mod v4 {
+    use super::{v5::v4, *};
+    use sp_std::prelude::*;
+
+    /// Layout of the storage items from version 1 to 3.
+    pub mod v3 {
+        use super::*;
+        use codec::{Decode, Encode};
+
+        #[derive(
+            Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound,
+        )]
+        pub struct OracleValue<SourceLimit: Get<u32>> {
+            pub value: u128,
+            pub source: BoundedVec<u8, SourceLimit>,
+            pub updated_at: u64,
+            pub status: OracleStatus,
+        }
+
+        #[derive(Encode, Decode)]
+        pub struct QuarantinedValue<AccountId, SourceLimit: Get<u32>, OperatorLimit: Get<u32>> {
+            pub value: OracleValue<SourceLimit>,
+            pub deviation: Perbill,
+            pub reporters: BoundedVec<AccountId, OperatorLimit>,
+        }
+
+        #[frame_support::storage_alias]
+        pub type LastValue<T: Config> = StorageMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as Config>::FeedId,
+            OracleValue<<T as Config>::MaxSourceLen>,
+            OptionQuery,
+        >;
+
+        #[frame_support::storage_alias]
+        pub type History<T: Config> = StorageDoubleMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as Config>::FeedId,
+            Blake2_128Concat,
+            u64,
+            OracleValue<<T as Config>::MaxSourceLen>,
+            OptionQuery,
+        >;
+    }
+
+    /// Quarantined value in the version 3 layout
+    pub type V3QuarantinedValue<T> = v3::QuarantinedValue<
+        <T as frame_system::Config>::AccountId,
+        <T as Config>::MaxSourceLen,
+        <T as Config>::MaxOperators,
+    >;
+
+    /// The spread of values published before version 4 is unknown and recorded as zero
+    fn translate_value<T: Config>(
+        old: v3::OracleValue<T::MaxSourceLen>,
+    ) -> v4::OracleValue<T::MaxSourceLen> {
+        v4::OracleValue {
+            value: old.value,
+            source: old.source,
+            updated_at: old.updated_at,
+            status: old.status,
+            confidence: 0,
+        }
+    }
+
+    /// Adds a zero confidence to stored and quarantined values.
+    pub struct MigrateToV4<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 3 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v4 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+
+            v4::LastValue::<T>::translate::<v3::OracleValue<T::MaxSourceLen>, _>(|_, old| {
+                translated.saturating_inc();
+                Some(translate_value::<T>(old))
+            });
+            v4::History::<T>::translate::<v3::OracleValue<T::MaxSourceLen>, _>(|_, _, old| {
+                translated.saturating_inc();
+                Some(translate_value::<T>(old))
+            });
+            v4::Quarantine::<T>::translate::<V3QuarantinedValue<T>, _>(|_, old| {
+                translated.saturating_inc();
+                Some(v4::QuarantinedValue {
+                    value: translate_value::<T>(old.value),
+                    deviation: old.deviation,
+                    reporters: old.reporters,
+                })
+            });
+
+            StorageVersion::new(4).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "migrated {} values to v4", translated);
+
+            T::DbWeight::get()
+                .reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let counts = (
+                LastValue::<T>::iter_keys().count() as u64,
+                History::<T>::iter_keys().count() as u64,
+                Quarantine::<T>::iter_keys().count() as u64,
+            );
+            Ok(counts.encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (last_values, history, quarantined): (u64, u64, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 4,
+                "storage version was not updated to v4"
+            );
+            ensure!(
+                v4::LastValue::<T>::iter().count() as u64 == last_values,
+                "LastValue entries do not decode after the migration"
+            );
+            ensure!(
+                v4::History::<T>::iter().count() as u64 == history,
+                "History entries do not decode after the migration"
+            );
+            ensure!(
+                v4::Quarantine::<T>::iter().count() as u64 == quarantined,
+                "Quarantine entries do not decode after the migration"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Add the observation time to oracle values and submissions and the maximum observation age to
+/// feed configurations.
+pub mod v5 {
+    use super::*;
+    use sp_std::prelude::*;
+
+    /// Layout of the storage items in version 4, and of feed configurations and rounds from
+    /// versions 2 and 1 on.
+    pub mod v4 {
+        use super::*;
+        use codec::{Decode, Encode};
+
+        #[derive(
+            Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound,
+        )]
+        pub struct OracleValue<SourceLimit: Get<u32>> {
+            pub value: u128,
+            pub source: BoundedVec<u8, SourceLimit>,
+            pub updated_at: u64,
+            pub status: OracleStatus,
+            pub confidence: u128,
+        }
+
+        #[derive(Encode, Decode)]
+        pub struct QuarantinedValue<AccountId, SourceLimit: Get<u32>, OperatorLimit: Get<u32>> {
+            pub value: OracleValue<SourceLimit>,
+            pub deviation: Perbill,
+            pub reporters: BoundedVec<AccountId, OperatorLimit>,
+        }
+
+        #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+        pub struct FeedConfig {
+            pub heartbeat: Option<u64>,
+            pub max_deviation: Option<Perbill>,
+            pub deviation_window: u64,
+            pub decimals: u8,
+        }
+
+        #[derive(Encode, Decode)]
+        pub struct Submission<AccountId, SourceLimit: Get<u32>> {
+            pub operator: AccountId,
+            pub value: u128,
+            pub source: BoundedVec<u8, SourceLimit>,
+            pub status: OracleStatus,
+        }
+
+        #[derive(Encode, Decode)]
+        pub struct Round<AccountId, BlockNumber, SourceLimit: Get<u32>, OperatorLimit: Get<u32>> {
+            pub id: u64,
+            pub deadline: BlockNumber,
+            pub submissions: BoundedVec<Submission<AccountId, SourceLimit>, OperatorLimit>,
+        }
+
+        /// Quarantined value in the version 4 layout
+        pub type QuarantinedValueOf<T> = QuarantinedValue<
+            <T as frame_system::Config>::AccountId,
+            <T as Config>::MaxSourceLen,
+            <T as Config>::MaxOperators,
+        >;
+
+        /// Round in the version 4 layout
+        pub type RoundOf<T> = Round<
+            <T as frame_system::Config>::AccountId,
+            BlockNumberFor<T>,
+            <T as Config>::MaxSourceLen,
+            <T as Config>::MaxOperators,
+        >;
+
+        #[frame_support::storage_alias]
+        pub type LastValue<T: Config> = StorageMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as Config>::FeedId,
+            OracleValue<<T as Config>::MaxSourceLen>,
+            OptionQuery,
+        >;
+
+        #[frame_support::storage_alias]
+        pub type History<T: Config> = StorageDoubleMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as Config>::FeedId,
+            Blake2_128Concat,
+            u64,
+            OracleValue<<T as Config>::MaxSourceLen>,
+            OptionQuery,
+        >;
+
+        #[frame_support::storage_alias]
+        pub type Quarantine<T: Config> = StorageMap<
+            Pallet<T>,
+            Blake2_128Concat,
+            <T as Config>::FeedId,
+            QuarantinedValueOf<T>,
+            OptionQuery,
+        >;
+
+        #[frame_support::storage_alias]
+        pub type FeedConfigs<T: Config> =
+            StorageMap<Pallet<T>, Blake2_128Concat, <T as Config>::FeedId, FeedConfig, OptionQuery>;
+
+        #[frame_support::storage_alias]
+        pub type Rounds<T: Config> =
+            StorageMap<Pallet<T>, Blake2_128Concat, <T as Config>::FeedId, RoundOf<T>, OptionQuery>;
+    }
+
+    /// Values published before version 5 are recorded as observed when they were published
+    fn translate_value<T: Config>(old: v4::OracleValue<T::MaxSourceLen>) -> OracleValueOf<T> {
+        OracleValue {
+            value: old.value,
+            source: old.source,
+            updated_at: old.updated_at,
+            status: old.status,
+            confidence: old.confidence,
+            observed_at: old.updated_at,
+        }
+    }
+
+    /// Records values as observed when they were published, open submissions as observed at the
+    /// time of the upgrade and feed configurations without a maximum observation age.
+    pub struct MigrateToV5<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 4 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v5 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+
+            LastValue::<T>::translate::<v4::OracleValue<T::MaxSourceLen>, _>(|_, old| {
+                translated.saturating_inc();
+                Some(translate_value::<T>(old))
+            });
+            History::<T>::translate::<v4::OracleValue<T::MaxSourceLen>, _>(|_, _, old| {
+                translated.saturating_inc();
+                Some(translate_value::<T>(old))
+            });
+            Quarantine::<T>::translate::<v4::QuarantinedValueOf<T>, _>(|_, old| {
+                translated.saturating_inc();
+                Some(QuarantinedValue {
+                    value: translate_value::<T>(old.value),
+                    deviation: old.deviation,
+                    reporters: old.reporters,
+                })
+            });
+            FeedConfigs::<T>::translate::<v4::FeedConfig, _>(|_, old| {
+                translated.saturating_inc();
+                Some(FeedConfig {
+                    heartbeat: old.heartbeat,
+                    max_deviation: old.max_deviation,
+                    deviation_window: old.deviation_window,
+                    decimals: old.decimals,
+                    max_observation_age: None,
+                })
+            });
+            // Open submissions are no older than the last published value
+            let now = Pallet::<T>::now();
+            Rounds::<T>::translate::<v4::RoundOf<T>, _>(|_, old| {
+                translated.saturating_inc();
+                let submissions = old
+                    .submissions
+                    .into_iter()
+                    .map(|s| Submission {
+                        operator: s.operator,
+                        value: s.value,
+                        source: s.source,
+                        status: s.status,
+                        observed_at: now,
+                    })
+                    .collect::<Vec<_>>();
+                Some(Round {
+                    id: old.id,
+                    deadline: old.deadline,
+                    submissions: BoundedVec::truncate_from(submissions),
+                })
+            });
+
+            StorageVersion::new(5).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "migrated {} entries to v5", translated);
+
+            T::DbWeight::get()
+                .reads_writes(translated.saturating_add(2), translated.saturating_add(1))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let counts = (
+                LastValue::<T>::iter_keys().count() as u64,
+                History::<T>::iter_keys().count() as u64,
+                Quarantine::<T>::iter_keys().count() as u64,
+                FeedConfigs::<T>::iter_keys().count() as u64,
+                Rounds::<T>::iter_keys().count() as u64,
+            );
+            Ok(counts.encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let (last_values, history, quarantined, configs, rounds): (u64, u64, u64, u64, u64) =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 5,
+                "storage version was not updated to v5"
+            );
+            ensure!(
+                LastValue::<T>::iter().count() as u64 == last_values,
+                "LastValue entries do not decode after the migration"
+            );
+            ensure!(
+                History::<T>::iter().count() as u64 == history,
+                "History entries do not decode after the migration"
+            );
+            ensure!(
+                Quarantine::<T>::iter().count() as u64 == quarantined,
+                "Quarantine entries do not decode after the migration"
+            );
+            ensure!(
+                FeedConfigs::<T>::iter().count() as u64 == configs,
+                "FeedConfigs entries do not decode after the migration"
+            );
+            ensure!(
+                Rounds::<T>::iter().count() as u64 == rounds,
+                "Rounds entries do not decode after the migration"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Index open rounds by the block they close at, instead of scanning every round each block.
+pub mod v6 {
+    use super::*;
+    use sp_std::prelude::*;
+
+    /// Indexes every open round at its deadline. Rounds past their deadline are moved to the
+    /// upgrade block, whose `on_initialize` runs after the upgrade and closes them.
+    pub struct MigrateToV6<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = Pallet::<T>::on_chain_storage_version();
+            if on_chain_version != 5 {
+                log::info!(
+                    target: "runtime::roracle",
+                    "skipping v6 migration: on-chain storage version is {:?}",
+                    on_chain_version,
+                );
+                return T::DbWeight::get().reads(1);
+            }
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut indexed = 0u64;
+
+            Rounds::<T>::translate::<Round<T>, _>(|feed, mut round| {
+                indexed.saturating_inc();
+                round.deadline = round.deadline.max(now);
+                RoundDeadlines::<T>::insert(round.deadline, feed, ());
+                Some(round)
+            });
+
+            StorageVersion::new(6).put::<Pallet<T>>();
+
+            log::info!(target: "runtime::roracle", "indexed {} open rounds in v6", indexed);
+
+            T::DbWeight::get().reads_writes(
+                indexed.saturating_add(2),
+                indexed.saturating_mul(2).saturating_add(1),
+            )
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            Ok((Rounds::<T>::iter_keys().count() as u64).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let rounds: u64 =
+                Decode::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+
+            ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 6,
+                "storage version was not updated to v6"
+            );
+            ensure!(
+                RoundDeadlines::<T>::iter_keys().count() as u64 == rounds,
+                "open rounds were not indexed"
+            );
+            for (feed, round) in Rounds::<T>::iter() {
+                ensure!(
+                    RoundDeadlines::<T>::contains_key(round.deadline, feed),
+                    "open round is not indexed at its deadline"
+                );
+            }
+            Ok(())
+        }
+    }
+}
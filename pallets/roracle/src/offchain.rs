@@ -0,0 +1,320 @@
+//! Offchain worker fetching prices from exchanges with failover
+//!
+//! Every `FetchInterval` blocks the worker walks the configured sources of each feed in priority
+//! order and submits the first price it obtains from every local operator key, reporting the
+//! source and how many sources failed before it. Submissions are signed payloads sent as unsigned
+//! transactions, so operators pay no fees.
+
+use crate::{fixed, pallet::*};
+use frame_support::pallet_prelude::*;
+use frame_system::{
+    offchain::{AppCrypto, SendUnsignedTransaction, Signer},
+    pallet_prelude::BlockNumberFor,
+};
+use lite_json::json::JsonValue;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+    offchain::{http, Duration},
+    traits::{IdentifyAccount, Zero},
+    RuntimeAppPublic,
+};
+use sp_std::prelude::*;
+
+/// Key type of the operator keys used by the offchain worker
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"rorc");
+
+/// Decimals prices are parsed with before they are rescaled to the decimals of their feed
+pub const PRICE_DECIMALS: u32 = 12;
+
+/// Time in milliseconds a single source has to respond
+pub const FETCH_TIMEOUT_MS: u64 = 2_000;
+
+/// Operator keys for signing offchain worker transactions
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// Signing scheme of operator keys for runtimes using `MultiSignature`
+    pub struct OperatorAuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OperatorAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+}
+
+/// Response layout of a price source
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum PriceFormat {
+    /// `{"price": "…"}`, as returned by the Binance `ticker/price` endpoint
+    Binance,
+    /// `{"data": {"amount": "…"}}`, as returned by the Coinbase `prices/{pair}/spot` endpoint
+    Coinbase,
+    /// `{"result": {"<pair>": {"c": ["…", …]}}}`, as returned by the Kraken `Ticker` endpoint
+    Kraken,
+}
+
+/// HTTP endpoint a feed price is fetched from
+#[derive(
+    Encode,
+    Decode,
+    CloneNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(NameLimit, UrlLimit))]
+#[codec(mel_bound())]
+pub struct PriceSource<NameLimit: Get<u32>, UrlLimit: Get<u32>> {
+    /// Name reported as the source of submitted values
+    pub name: BoundedVec<u8, NameLimit>,
+    pub url: BoundedVec<u8, UrlLimit>,
+    pub format: PriceFormat,
+}
+
+/// Reasons a source did not yield a price
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum FetchError {
+    /// URL is not valid UTF-8
+    InvalidUrl,
+    /// Request could not be sent or failed
+    Http,
+    /// Source did not respond within `FETCH_TIMEOUT_MS`
+    DeadlineReached,
+    /// Source responded with a status other than 200
+    UnexpectedStatus(u16),
+    /// Response does not contain a price in the expected format
+    InvalidResponse,
+}
+
+impl<T: Config> Pallet<T> {
+    /// Fetch and submit prices of every feed with configured sources
+    pub(crate) fn fetch_and_submit_prices(now: BlockNumberFor<T>) {
+        let interval = T::FetchInterval::get();
+        if interval.is_zero() || !(now % interval).is_zero() {
+            return;
+        }
+
+        let operator_keys = Self::local_operator_keys();
+        if operator_keys.is_empty() {
+            log::debug!(target: "runtime::roracle", "no local operator keys, skipping price fetch");
+            return;
+        }
+
+        for (feed, sources) in FeedSources::<T>::iter() {
+            // Submissions to paused feeds would only be rejected
+            if !Self::is_active(feed) {
+                continue;
+            }
+
+            // Keys that already reported to the open round would only be rejected
+            let submitted: Vec<T::AccountId> = Rounds::<T>::get(feed)
+                .map(|round| round.submissions.into_iter().map(|s| s.operator).collect())
+                .unwrap_or_default();
+            let keys: Vec<T::Public> = operator_keys
+                .iter()
+                .filter(|key| !submitted.contains(&(*key).clone().into_account()))
+                .cloned()
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let Some((price, source, status)) = Self::fetch_with_failover(&sources) else {
+                log::warn!(target: "runtime::roracle", "all sources of feed {:?} failed", feed);
+                continue;
+            };
+            let decimals = Self::decimals(feed);
+            let Some(value) =
+                fixed::rescale(price, PRICE_DECIMALS as u8, decimals).filter(|value| *value > 0)
+            else {
+                log::warn!(
+                    target: "runtime::roracle",
+                    "price {} of feed {:?} cannot be expressed with {} decimals",
+                    price,
+                    feed,
+                    decimals,
+                );
+                continue;
+            };
+
+            // Prices are fetched after the latest block, so its time is the latest observation
+            // time that is not ahead of the chain when the submission is validated
+            let observed_at = Self::now();
+            let round = Self::current_round(feed);
+            let results = Signer::<T, T::AuthorityId>::all_accounts()
+                .with_filter(keys)
+                .send_unsigned_transaction(
+                    |account| OraclePayload {
+                        feed,
+                        round,
+                        value,
+                        source: source.to_vec(),
+                        status,
+                        observed_at: Some(observed_at),
+                        block_number: now,
+                        public: account.public.clone(),
+                    },
+                    |payload, signature| Call::submit_oracle_value_unsigned_with_signed_payload {
+                        payload,
+                        signature,
+                    },
+                );
+            for (account, result) in results {
+                if result.is_err() {
+                    log::error!(
+                        target: "runtime::roracle",
+                        "failed to submit price of feed {:?} from {:?}",
+                        feed,
+                        account.id,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Local keys of accounts in the operator set
+    fn local_operator_keys() -> Vec<T::Public> {
+        <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .map(|key| {
+                let generic: <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic =
+                    key.into();
+                generic.into()
+            })
+            .filter(|key: &T::Public| Self::is_operator(&key.clone().into_account()))
+            .collect()
+    }
+
+    /// First price obtained from `sources` in priority order with its source and status
+    pub fn fetch_with_failover(
+        sources: &[PriceSourceOf<T>],
+    ) -> Option<(u128, SourceOf<T>, OracleStatus)> {
+        for (level, source) in sources.iter().enumerate() {
+            match fetch_price(source) {
+                Ok(value) if value > 0 => {
+                    let status = match level {
+                        0 => OracleStatus::Primary,
+                        // `set_feed_sources` keeps the level within `MaxFailoverLevel`
+                        level => OracleStatus::Failover(level as u8),
+                    };
+                    return Some((value, source.name.clone(), status));
+                }
+                Ok(_) => log::warn!(
+                    target: "runtime::roracle",
+                    "source {:?} returned a zero price",
+                    sp_std::str::from_utf8(&source.name).unwrap_or_default(),
+                ),
+                Err(err) => log::warn!(
+                    target: "runtime::roracle",
+                    "source {:?} failed: {:?}",
+                    sp_std::str::from_utf8(&source.name).unwrap_or_default(),
+                    err,
+                ),
+            }
+        }
+        None
+    }
+}
+
+/// Fetch the price of a single source
+pub fn fetch_price<NameLimit: Get<u32>, UrlLimit: Get<u32>>(
+    source: &PriceSource<NameLimit, UrlLimit>,
+) -> Result<u128, FetchError> {
+    let url = sp_std::str::from_utf8(&source.url).map_err(|_| FetchError::InvalidUrl)?;
+    let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+
+    let pending = http::Request::get(url)
+        .deadline(deadline)
+        .send()
+        .map_err(|_| FetchError::Http)?;
+    let response = pending
+        .try_wait(deadline)
+        .map_err(|_| FetchError::DeadlineReached)?
+        .map_err(|_| FetchError::Http)?;
+    if response.code != 200 {
+        return Err(FetchError::UnexpectedStatus(response.code));
+    }
+
+    let body = response.body().collect::<Vec<u8>>();
+    parse_price(source.format, &body).ok_or(FetchError::InvalidResponse)
+}
+
+/// Extract the price from a response body
+pub fn parse_price(format: PriceFormat, body: &[u8]) -> Option<u128> {
+    let body = sp_std::str::from_utf8(body).ok()?;
+    let json = lite_json::parse_json(body).ok()?;
+
+    let price = match format {
+        PriceFormat::Binance => field(&json, "price")?,
+        PriceFormat::Coinbase => field(field(&json, "data")?, "amount")?,
+        PriceFormat::Kraken => {
+            let JsonValue::Object(pairs) = field(&json, "result")? else {
+                return None;
+            };
+            let (_, ticker) = pairs.first()?;
+            let JsonValue::Array(close) = field(ticker, "c")? else {
+                return None;
+            };
+            close.first()?
+        }
+    };
+
+    match price {
+        JsonValue::String(chars) => parse_decimal(chars),
+        _ => None,
+    }
+}
+
+/// Field `name` of a JSON object
+fn field<'a>(value: &'a JsonValue, name: &str) -> Option<&'a JsonValue> {
+    let JsonValue::Object(fields) = value else {
+        return None;
+    };
+    fields
+        .iter()
+        .find(|(key, _)| key.iter().copied().eq(name.chars()))
+        .map(|(_, value)| value)
+}
+
+/// Parse a non-negative decimal number into a value with `PRICE_DECIMALS` decimals, truncating
+/// further fraction digits
+pub fn parse_decimal(chars: &[char]) -> Option<u128> {
+    let mut integer = 0u128;
+    let mut fraction = 0u128;
+    let mut fraction_digits = 0u32;
+    let mut digits = 0u32;
+    let mut seen_point = false;
+
+    for c in chars {
+        match c {
+            '.' if !seen_point => seen_point = true,
+            '0'..='9' => {
+                let digit = c.to_digit(10)? as u128;
+                digits += 1;
+                if !seen_point {
+                    integer = integer.checked_mul(10)?.checked_add(digit)?;
+                } else if fraction_digits < PRICE_DECIMALS {
+                    fraction = fraction * 10 + digit;
+                    fraction_digits += 1;
+                }
+            }
+            _ => return None,
+        }
+    }
+    if digits == 0 {
+        return None;
+    }
+
+    integer
+        .checked_mul(10u128.pow(PRICE_DECIMALS))?
+        .checked_add(fraction * 10u128.pow(PRICE_DECIMALS - fraction_digits))
+}
@@ -1,18 +1,34 @@
 #![cfg(test)]
 
 use super::*;
+use crate::aggregation::AggregationMethod;
+use crate::offchain::{self, PriceFormat, PriceSource};
 use crate::pallet::*;
+use crate::{
+    candles::Candle,
+    traits::{MockPriceProvider, OnNewValue, PriceProvider},
+    twap::Observation,
+};
+use codec::{Decode, Encode};
 use frame_support::{
     assert_err, assert_ok,
-    traits::{ConstU64, GenesisBuild},
+    dispatch::{DispatchResult, GetDispatchInfo},
+    parameter_types,
+    traits::{ConstU32, ConstU64, GenesisBuild, Hooks},
+    weights::Weight,
+    BoundedVec,
 };
 use frame_system as system;
-use sp_core::H256;
+use sp_core::{
+    offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+    H256,
+};
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup},
-    BuildStorage,
+    testing::{TestSignature, TestXt, UintAuthorityId},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup, ValidateUnsigned},
+    transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidity},
+    BuildStorage, Perbill, Percent, RuntimeAppPublic,
 };
-use sp_std::marker::PhantomData;
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -52,17 +68,125 @@ impl system::Config for Test {
 }
 
 pub struct MockTimeProvider;
-impl frame_support::traits::Time for MockTimeProvider {
-    type Moment = u64;
+impl frame_support::traits::UnixTime for MockTimeProvider {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_secs(Now::get()) // Mock timestamp
+    }
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::traits::BenchmarkHelper for MockTimeProvider {
+    fn set_time(now: u64) {
+        Now::set(now);
+    }
+}
+
+type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = UintAuthorityId;
+    type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    type OverarchingCall = RuntimeCall;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: RuntimeCall,
+        _public: UintAuthorityId,
+        account: u64,
+        _nonce: u64,
+    ) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (account, ())))
+    }
+}
+
+/// Operator keys of the mock runtime, where a key signs for the account of the same number
+pub struct TestAuthId;
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+    type RuntimeAppPublic = UintAuthorityId;
+    type GenericPublic = UintAuthorityId;
+    type GenericSignature = TestSignature;
+}
+
+parameter_types! {
+    pub static Now: u64 = 1000;
+    pub static MinQuorum: u32 = 1;
+    pub static Aggregation: AggregationMethod = AggregationMethod::Median;
+    pub CandleIntervals: Vec<u64> = vec![60, 3600];
+    pub static NewValues: Vec<(u32, Option<u128>, u128)> = vec![];
+    pub static MaxHeartbeatChecks: u32 = 2;
+    pub static MaxRoundsPerBlock: u32 = 4;
+}
+
+/// Records every published value in `NewValues`
+pub struct RecordNewValues;
+impl OnNewValue<u32> for RecordNewValues {
+    fn on_new_value(feed: &u32, old: Option<u128>, new: u128) {
+        NewValues::mutate(|values| values.push((*feed, old, new)));
+    }
+
+    fn weight() -> Weight {
+        Weight::from_parts(1_000, 0)
+    }
+}
 
-    fn now() -> Self::Moment {
-        1000 // Mock timestamp
+pub const BTC_USD: u32 = 0;
+pub const ETH_USD: u32 = 1;
+
+/// Active feed accepting any non-zero value
+pub fn feed_info(base: &[u8], quote: &[u8]) -> FeedInfoOf<Test> {
+    FeedInfo {
+        base: BoundedVec::truncate_from(base.to_vec()),
+        quote: BoundedVec::truncate_from(quote.to_vec()),
+        description: Default::default(),
+        min_value: 1,
+        max_value: u128::MAX,
+        state: FeedState::Active,
     }
 }
 
 impl pallet_roracle::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type TimeProvider = MockTimeProvider;
+    type FeedId = u32;
+    type AdminOrigin = frame_system::EnsureRoot<u64>;
+    type MaxOperators = frame_support::traits::ConstU32<4>;
+    type MinQuorum = MinQuorum;
+    type RoundTimeout = ConstU64<5>;
+    type Aggregation = Aggregation;
+    type MaxSourceLen = frame_support::traits::ConstU32<64>;
+    type MaxHistory = frame_support::traits::ConstU32<5>;
+    type MaxFailoverLevel = frame_support::traits::ConstU8<2>;
+    type AuthorityId = TestAuthId;
+    type MaxSources = frame_support::traits::ConstU32<4>;
+    type MaxUrlLen = frame_support::traits::ConstU32<128>;
+    type MaxSymbolLen = frame_support::traits::ConstU32<8>;
+    type MaxDescriptionLen = frame_support::traits::ConstU32<64>;
+    type FetchInterval = ConstU64<1>;
+    type MaxUnsignedPerBlock = frame_support::traits::ConstU32<2>;
+    type UnsignedPriority = ConstU64<100>;
+    type UnsignedLongevity = ConstU64<5>;
+    type TwapGranularity = ConstU64<60>;
+    type TwapBuckets = frame_support::traits::ConstU32<5>;
+    type CandleIntervals = CandleIntervals;
+    type CandleRetention = frame_support::traits::ConstU32<3>;
+    type OnNewValue = RecordNewValues;
+    type MaxBatchSize = frame_support::traits::ConstU32<3>;
+    type MaxHeartbeatChecks = MaxHeartbeatChecks;
+    type MaxRoundsPerBlock = MaxRoundsPerBlock;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = MockTimeProvider;
+    type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
@@ -72,62 +196,146 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap();
 
     let genesis_config = pallet_roracle::GenesisConfig::<Test> {
-        _phantom: PhantomData,
+        operators: vec![1, 2, 3],
+        feeds: vec![
+            (BTC_USD, feed_info(b"BTC", b"USD"), FeedConfig::default()),
+            (ETH_USD, feed_info(b"ETH", b"USD"), FeedConfig::default()),
+        ],
+        ..Default::default()
     };
     genesis_config.assimilate_storage(&mut storage).unwrap();
 
-    storage.into()
+    let mut ext: sp_io::TestExternalities = storage.into();
+    // Events are not recorded in the genesis block
+    ext.execute_with(|| System::set_block_number(1));
+    ext
 }
 
 #[test]
 fn test_genesis_config() {
     new_test_ext().execute_with(|| {
-        // Check that history index is initialized to 0
-        assert_eq!(Roracle::history_index(), 0);
+        // Check that history index starts at 0
+        assert_eq!(Roracle::history_index(BTC_USD), 0);
         // Check that last value is None initially
-        assert!(Roracle::last_value().is_none());
+        assert!(Roracle::last_value(BTC_USD).is_none());
+        // Check that genesis operators are registered
+        assert_eq!(Roracle::operators().into_inner(), vec![1, 2, 3]);
+    });
+}
+
+#[test]
+fn test_genesis_feeds_and_values() {
+    let mut storage = system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    let config = FeedConfig {
+        heartbeat: Some(60),
+        decimals: 8,
+        ..Default::default()
+    };
+    pallet_roracle::GenesisConfig::<Test> {
+        operators: vec![3, 1, 3],
+        feeds: vec![
+            (BTC_USD, feed_info(b"BTC", b"USD"), config.clone()),
+            (ETH_USD, feed_info(b"ETH", b"USD"), FeedConfig::default()),
+        ],
+        values: vec![(BTC_USD, 5_000_000_000_000), (ETH_USD, 3_000_000_000)],
+        values_published_at: 900,
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    sp_io::TestExternalities::from(storage).execute_with(|| {
+        assert_eq!(Roracle::operators().into_inner(), vec![1, 3]);
+        assert_eq!(Roracle::feed_config(BTC_USD), Some(config));
+        assert_eq!(Roracle::feed_config(ETH_USD), Some(FeedConfig::default()));
+        assert_eq!(Roracle::feed_info(ETH_USD), Some(feed_info(b"ETH", b"USD")));
+
+        let seeded = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!(seeded.value, 5_000_000_000_000);
+        assert_eq!(seeded.source.to_vec(), b"genesis".to_vec());
+        assert_eq!(seeded.status, OracleStatus::Manual);
+        assert_eq!((seeded.updated_at, seeded.observed_at), (900, 900));
+        assert_eq!(Roracle::history(BTC_USD, 0), Some(seeded));
+        assert_eq!(Roracle::history_index(BTC_USD), 1);
+        assert_eq!(Roracle::last_value(ETH_USD).unwrap().value, 3_000_000_000);
+        assert_ok!(Roracle::do_try_state());
     });
 }
 
+#[test]
+#[should_panic(expected = "genesis values must be non-zero")]
+fn test_genesis_rejects_zero_value() {
+    let mut storage = system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_roracle::GenesisConfig::<Test> {
+        values: vec![(BTC_USD, 0)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "genesis values need a publication time")]
+fn test_genesis_values_require_publication_time() {
+    let mut storage = system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_roracle::GenesisConfig::<Test> {
+        feeds: vec![(BTC_USD, feed_info(b"BTC", b"USD"), FeedConfig::default())],
+        values: vec![(BTC_USD, 5_000_000_000_000)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+}
+
 #[test]
 fn test_submit_oracle_value_success() {
     new_test_ext().execute_with(|| {
         let account_id = 1;
         let value = 50000 * 1_000_000_000_000u128; // 50k with 12 decimals
         let source = b"Binance".to_vec();
-        let status = b"success".to_vec();
+        let status = OracleStatus::Primary;
 
         // Submit oracle value
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             value,
             source.clone(),
-            status.clone()
+            status,
+            None
         ));
 
         // Check last value
-        let last_value = Roracle::last_value().unwrap();
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
         assert_eq!(last_value.value, value);
         assert_eq!(last_value.source, source);
         assert_eq!(last_value.status, status);
         assert_eq!(last_value.updated_at, 1000); // Mock timestamp
 
         // Check history
-        let history_entry = Roracle::history(0);
+        let history_entry = Roracle::history(BTC_USD, 0).unwrap();
         assert_eq!(history_entry.value, value);
         assert_eq!(history_entry.source, source);
         assert_eq!(history_entry.status, status);
 
         // Check history index incremented
-        assert_eq!(Roracle::history_index(), 1);
+        assert_eq!(Roracle::history_index(BTC_USD), 1);
 
         // Check event was emitted
         system::Pallet::<Test>::assert_has_event(
             RuntimeEvent::Roracle(pallet_roracle::Event::ValueUpdated {
+                feed: BTC_USD,
                 value,
-                source: source.clone(),
+                source: source.clone().try_into().unwrap(),
                 updated_at: 1000,
-                status: status.clone(),
+                status,
+                confidence: 0,
+                observed_at: 1000,
             })
             .into(),
         );
@@ -140,22 +348,24 @@ fn test_submit_oracle_value_invalid_value_zero() {
         let account_id = 1;
         let value = 0u128; // Invalid: zero value
         let source = b"Binance".to_vec();
-        let status = b"success".to_vec();
+        let status = OracleStatus::Primary;
 
         // Should fail with InvalidValue error
         assert_err!(
             Roracle::submit_oracle_value(
                 RuntimeOrigin::signed(account_id),
+                BTC_USD,
                 value,
                 source,
-                status
+                status,
+                None
             ),
             pallet_roracle::Error::<Test>::InvalidValue
         );
 
         // Check that nothing was stored
-        assert!(Roracle::last_value().is_none());
-        assert_eq!(Roracle::history_index(), 0);
+        assert!(Roracle::last_value(BTC_USD).is_none());
+        assert_eq!(Roracle::history_index(BTC_USD), 0);
     });
 }
 
@@ -165,22 +375,24 @@ fn test_submit_oracle_value_invalid_source_empty() {
         let account_id = 1;
         let value = 50000 * 1_000_000_000_000u128;
         let source = vec![]; // Invalid: empty source
-        let status = b"success".to_vec();
+        let status = OracleStatus::Primary;
 
         // Should fail with InvalidSource error
         assert_err!(
             Roracle::submit_oracle_value(
                 RuntimeOrigin::signed(account_id),
+                BTC_USD,
                 value,
                 source,
-                status
+                status,
+                None
             ),
             pallet_roracle::Error::<Test>::InvalidSource
         );
 
         // Check that nothing was stored
-        assert!(Roracle::last_value().is_none());
-        assert_eq!(Roracle::history_index(), 0);
+        assert!(Roracle::last_value(BTC_USD).is_none());
+        assert_eq!(Roracle::history_index(BTC_USD), 0);
     });
 }
 
@@ -189,15 +401,17 @@ fn test_submit_oracle_value_requires_signed() {
     new_test_ext().execute_with(|| {
         let value = 50000 * 1_000_000_000_000u128;
         let source = b"Binance".to_vec();
-        let status = b"success".to_vec();
+        let status = OracleStatus::Primary;
 
         // Should fail with BadOrigin
         assert_err!(
             Roracle::submit_oracle_value(
                 RuntimeOrigin::none(),
+                BTC_USD,
                 value,
                 source,
-                status
+                status,
+                None
             ),
             sp_runtime::DispatchError::BadOrigin
         );
@@ -212,35 +426,39 @@ fn test_multiple_submissions() {
         // Submit first value
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             50000 * 1_000_000_000_000u128,
             b"Binance".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Submit second value
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             51000 * 1_000_000_000_000u128,
             b"Coinbase".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Check last value is the second one
-        let last_value = Roracle::last_value().unwrap();
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
         assert_eq!(last_value.value, 51000 * 1_000_000_000_000u128);
         assert_eq!(last_value.source, b"Coinbase".to_vec());
 
         // Check history has both entries
-        let history_0 = Roracle::history(0);
+        let history_0 = Roracle::history(BTC_USD, 0).unwrap();
         assert_eq!(history_0.value, 50000 * 1_000_000_000_000u128);
         assert_eq!(history_0.source, b"Binance".to_vec());
 
-        let history_1 = Roracle::history(1);
+        let history_1 = Roracle::history(BTC_USD, 1).unwrap();
         assert_eq!(history_1.value, 51000 * 1_000_000_000_000u128);
         assert_eq!(history_1.source, b"Coinbase".to_vec());
 
         // Check history index
-        assert_eq!(Roracle::history_index(), 2);
+        assert_eq!(Roracle::history_index(BTC_USD), 2);
     });
 }
 
@@ -252,31 +470,46 @@ fn test_different_sources() {
         // Test Binance
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             50000 * 1_000_000_000_000u128,
             b"Binance".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Test Coinbase
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             51000 * 1_000_000_000_000u128,
             b"Coinbase".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Test Kraken
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             52000 * 1_000_000_000_000u128,
             b"Kraken".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Verify all sources are stored correctly
-        assert_eq!(Roracle::history(0).source, b"Binance".to_vec());
-        assert_eq!(Roracle::history(1).source, b"Coinbase".to_vec());
-        assert_eq!(Roracle::history(2).source, b"Kraken".to_vec());
+        assert_eq!(
+            Roracle::history(BTC_USD, 0).unwrap().source,
+            b"Binance".to_vec()
+        );
+        assert_eq!(
+            Roracle::history(BTC_USD, 1).unwrap().source,
+            b"Coinbase".to_vec()
+        );
+        assert_eq!(
+            Roracle::history(BTC_USD, 2).unwrap().source,
+            b"Kraken".to_vec()
+        );
     });
 }
 
@@ -285,34 +518,49 @@ fn test_different_statuses() {
     new_test_ext().execute_with(|| {
         let account_id = 1;
 
-        // Test success status
+        // Test primary status
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             50000 * 1_000_000_000_000u128,
             b"Binance".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
-        // Test warning status
+        // Test failover status
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             51000 * 1_000_000_000_000u128,
             b"Coinbase".to_vec(),
-            b"warning".to_vec()
+            OracleStatus::Failover(1),
+            None
         ));
 
-        // Test error status
+        // Test degraded status
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             52000 * 1_000_000_000_000u128,
             b"Kraken".to_vec(),
-            b"error".to_vec()
+            OracleStatus::Degraded,
+            None
         ));
 
         // Verify all statuses are stored correctly
-        assert_eq!(Roracle::history(0).status, b"success".to_vec());
-        assert_eq!(Roracle::history(1).status, b"warning".to_vec());
-        assert_eq!(Roracle::history(2).status, b"error".to_vec());
+        assert_eq!(
+            Roracle::history(BTC_USD, 0).unwrap().status,
+            OracleStatus::Primary
+        );
+        assert_eq!(
+            Roracle::history(BTC_USD, 1).unwrap().status,
+            OracleStatus::Failover(1)
+        );
+        assert_eq!(
+            Roracle::history(BTC_USD, 2).unwrap().status,
+            OracleStatus::Degraded
+        );
     });
 }
 
@@ -324,12 +572,14 @@ fn test_large_values() {
 
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             large_value,
             b"Binance".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
-        let last_value = Roracle::last_value().unwrap();
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
         assert_eq!(last_value.value, large_value);
     });
 }
@@ -342,12 +592,14 @@ fn test_long_source_string() {
 
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             50000 * 1_000_000_000_000u128,
             long_source.clone(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
-        let last_value = Roracle::last_value().unwrap();
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
         assert_eq!(last_value.source, long_source);
     });
 }
@@ -358,18 +610,2850 @@ fn test_history_index_overflow() {
         let account_id = 1;
 
         // Set history index to near max
-        HistoryIndex::<Test>::put(u64::MAX - 1);
+        HistoryIndex::<Test>::insert(BTC_USD, u64::MAX - 1);
 
         // Submit value
         assert_ok!(Roracle::submit_oracle_value(
             RuntimeOrigin::signed(account_id),
+            BTC_USD,
             50000 * 1_000_000_000_000u128,
             b"Binance".to_vec(),
-            b"success".to_vec()
+            OracleStatus::Primary,
+            None
         ));
 
         // Check that index saturates at max
-        assert_eq!(Roracle::history_index(), u64::MAX);
+        assert_eq!(Roracle::history_index(BTC_USD), u64::MAX);
+    });
+}
+
+#[test]
+fn test_feeds_are_independent() {
+    new_test_ext().execute_with(|| {
+        let account_id = 1;
+
+        // Submit BTC/USD value
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(account_id),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Submit ETH/USD values
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(account_id),
+            ETH_USD,
+            3000 * 1_000_000_000_000u128,
+            b"Coinbase".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(account_id),
+            ETH_USD,
+            3100 * 1_000_000_000_000u128,
+            b"Kraken".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Each feed keeps its own last value
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            50000 * 1_000_000_000_000u128
+        );
+        assert_eq!(
+            Roracle::last_value(ETH_USD).unwrap().value,
+            3100 * 1_000_000_000_000u128
+        );
+
+        // Each feed keeps its own history counter
+        assert_eq!(Roracle::history_index(BTC_USD), 1);
+        assert_eq!(Roracle::history_index(ETH_USD), 2);
+        assert_eq!(
+            Roracle::history(ETH_USD, 0).unwrap().source,
+            b"Coinbase".to_vec()
+        );
+        assert_eq!(
+            Roracle::history(ETH_USD, 1).unwrap().source,
+            b"Kraken".to_vec()
+        );
+
+        // Events carry the feed
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::ValueUpdated {
+                feed: ETH_USD,
+                value: 3100 * 1_000_000_000_000u128,
+                source: b"Kraken".to_vec().try_into().unwrap(),
+                updated_at: 1000,
+                status: OracleStatus::Primary,
+                confidence: 0,
+                observed_at: 1000,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_submit_oracle_value_requires_operator() {
+    new_test_ext().execute_with(|| {
+        // Account 4 is not an operator
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(4),
+                BTC_USD,
+                50000 * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            pallet_roracle::Error::<Test>::NotOperator
+        );
+
+        // Check that nothing was stored
+        assert!(Roracle::last_value(BTC_USD).is_none());
+    });
+}
+
+#[test]
+fn test_add_and_remove_operator() {
+    new_test_ext().execute_with(|| {
+        // Only the admin origin can manage operators
+        assert_err!(
+            Roracle::add_operator(RuntimeOrigin::signed(1), 4),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(Roracle::add_operator(RuntimeOrigin::root(), 4));
+        assert!(Roracle::is_operator(&4));
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::OperatorAdded { who: 4 }).into(),
+        );
+
+        // New operator can submit
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(4),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Duplicates and overflow are rejected
+        assert_err!(
+            Roracle::add_operator(RuntimeOrigin::root(), 4),
+            pallet_roracle::Error::<Test>::AlreadyOperator
+        );
+        assert_err!(
+            Roracle::add_operator(RuntimeOrigin::root(), 5),
+            pallet_roracle::Error::<Test>::TooManyOperators
+        );
+
+        assert_ok!(Roracle::remove_operator(RuntimeOrigin::root(), 4));
+        assert!(!Roracle::is_operator(&4));
+        assert_err!(
+            Roracle::remove_operator(RuntimeOrigin::root(), 4),
+            pallet_roracle::Error::<Test>::NotOperator
+        );
+    });
+}
+
+#[test]
+fn test_rotate_operator() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Roracle::rotate_operator(RuntimeOrigin::root(), 1, 10, 0));
+        assert_eq!(Roracle::operators().into_inner(), vec![2, 3, 10]);
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::OperatorRotated { old: 1, new: 10 })
+                .into(),
+        );
+
+        // Old key can no longer submit
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                50000 * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            pallet_roracle::Error::<Test>::NotOperator
+        );
+
+        // Rotating onto an existing operator fails
+        assert_err!(
+            Roracle::rotate_operator(RuntimeOrigin::root(), 2, 3, 0),
+            pallet_roracle::Error::<Test>::AlreadyOperator
+        );
+    });
+}
+
+#[test]
+fn test_rotate_operator_keeps_submissions_of_open_rounds() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(2);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Every open round must be accounted for
+        assert_err!(
+            Roracle::rotate_operator(RuntimeOrigin::root(), 1, 10, 0),
+            Error::<Test>::InvalidWitness
+        );
+        assert_ok!(Roracle::rotate_operator(RuntimeOrigin::root(), 1, 10, 1));
+        let round = Roracle::round(BTC_USD).unwrap();
+        assert_eq!(
+            round
+                .submissions
+                .iter()
+                .map(|s| s.operator)
+                .collect::<Vec<_>>(),
+            vec![10]
+        );
+
+        // The new key reports for the operator that already submitted to the round
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(10),
+                BTC_USD,
+                51000 * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            Error::<Test>::AlreadySubmitted
+        );
+        assert!(Roracle::round(BTC_USD).is_some());
+    });
+}
+
+#[test]
+fn test_round_waits_for_quorum() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(2),
+            BTC_USD,
+            52000 * 1_000_000_000_000u128,
+            b"Kraken".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Nothing is published before quorum
+        assert!(Roracle::last_value(BTC_USD).is_none());
+        assert_eq!(Roracle::round(BTC_USD).unwrap().submissions.len(), 2);
+
+        // An operator can only submit once per round
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                50500 * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            pallet_roracle::Error::<Test>::AlreadySubmitted
+        );
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(3),
+            BTC_USD,
+            51000 * 1_000_000_000_000u128,
+            b"Coinbase".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Median is published with the source of the median submission
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!(last_value.value, 51000 * 1_000_000_000_000u128);
+        assert_eq!(last_value.source, b"Coinbase".to_vec());
+        assert_eq!(last_value.confidence, 1000 * 1_000_000_000_000u128);
+        assert_eq!(Roracle::history_index(BTC_USD), 1);
+        assert!(Roracle::round(BTC_USD).is_none());
+        // A round closed by quorum no longer waits for its deadline
+        assert_eq!(RoundDeadlines::<Test>::iter().count(), 0);
+        assert_eq!(Roracle::next_round_id(BTC_USD), 1);
+
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::RoundFinalized {
+                feed: BTC_USD,
+                round: 0,
+                value: 51000 * 1_000_000_000_000u128,
+                participants: 3,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_round_finalized_at_deadline() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(2),
+            BTC_USD,
+            51000 * 1_000_000_000_000u128,
+            b"Coinbase".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Round opened in block 1 with a timeout of 5 blocks
+        assert_eq!(Roracle::round(BTC_USD).unwrap().deadline, 6);
+        assert!(RoundDeadlines::<Test>::contains_key(6, BTC_USD));
+
+        System::set_block_number(5);
+        Roracle::on_initialize(5);
+        assert!(Roracle::last_value(BTC_USD).is_none());
+
+        System::set_block_number(6);
+        Roracle::on_initialize(6);
+
+        // Median of an even number of submissions is the mean of the middle two
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!(last_value.value, 50500 * 1_000_000_000_000u128);
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::RoundFinalized {
+                feed: BTC_USD,
+                round: 0,
+                value: 50500 * 1_000_000_000_000u128,
+                participants: 2,
+            })
+            .into(),
+        );
+        assert_eq!(RoundDeadlines::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn test_expired_rounds_beyond_limit_close_in_next_block() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+        MaxRoundsPerBlock::set(1);
+        for feed in [BTC_USD, ETH_USD] {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                feed,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // Only one of the rounds closes at their deadline, the other one in the next block
+        System::set_block_number(6);
+        Roracle::on_initialize(6);
+        assert_eq!(Rounds::<Test>::iter().count(), 1);
+        assert_eq!(RoundDeadlineCursor::<Test>::get(), Some(6));
+        assert_ok!(Roracle::do_try_state());
+
+        System::set_block_number(7);
+        Roracle::on_initialize(7);
+        assert_eq!(Rounds::<Test>::iter().count(), 0);
+        assert!(Roracle::last_value(BTC_USD).is_some());
+        assert!(Roracle::last_value(ETH_USD).is_some());
+        assert_eq!(RoundDeadlineCursor::<Test>::get(), None);
+    });
+}
+
+#[test]
+fn test_trimmed_mean_aggregation() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(4);
+        Aggregation::set(AggregationMethod::TrimmedMean(Percent::from_percent(25)));
+        assert_ok!(Roracle::add_operator(RuntimeOrigin::root(), 4));
+
+        for (who, value) in [(1, 100u128), (2, 200), (3, 300), (4, 10_000)] {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(who),
+                BTC_USD,
+                value,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // Lowest and highest submissions are dropped
+        let last_value = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!(last_value.value, 250);
+        // Median distance to the aggregate, which the outlier does not widen
+        assert_eq!(last_value.confidence, 100);
+    });
+}
+
+#[test]
+fn test_weighted_median_aggregation() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+        Aggregation::set(AggregationMethod::WeightedMedian);
+        assert_ok!(Roracle::set_operator_weight(RuntimeOrigin::root(), 3, 5));
+        assert_eq!(Roracle::operator_weight(3), 5);
+
+        for (who, value) in [(1, 100u128), (2, 200), (3, 300)] {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(who),
+                BTC_USD,
+                value,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // Operator 3 outweighs the other two together
+        assert_eq!(Roracle::last_value(BTC_USD).unwrap().value, 300);
+    });
+}
+
+#[test]
+fn test_source_is_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                50000 * 1_000_000_000_000u128,
+                vec![b'A'; 65],
+                OracleStatus::Primary,
+                None
+            ),
+            pallet_roracle::Error::<Test>::SourceTooLong
+        );
+
+        // Check that nothing was stored
+        assert!(Roracle::round(BTC_USD).is_none());
+        assert!(Roracle::last_value(BTC_USD).is_none());
     });
 }
 
+#[test]
+fn test_invalid_status_rejected() {
+    new_test_ext().execute_with(|| {
+        // Stale is reserved for the pallet, failover levels start at 1 and are capped
+        for status in [
+            OracleStatus::Stale,
+            OracleStatus::Failover(0),
+            OracleStatus::Failover(3),
+        ] {
+            assert_err!(
+                Roracle::submit_oracle_value(
+                    RuntimeOrigin::signed(1),
+                    BTC_USD,
+                    50000 * 1_000_000_000_000u128,
+                    b"Binance".to_vec(),
+                    status,
+                    None
+                ),
+                pallet_roracle::Error::<Test>::InvalidStatus
+            );
+        }
+
+        // Failover depth is exposed in events
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Kraken".to_vec(),
+            OracleStatus::Failover(2),
+            None
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD)
+                .unwrap()
+                .status
+                .failover_depth(),
+            2
+        );
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::ValueUpdated {
+                feed: BTC_USD,
+                value: 50000 * 1_000_000_000_000u128,
+                source: b"Kraken".to_vec().try_into().unwrap(),
+                updated_at: 1000,
+                status: OracleStatus::Failover(2),
+                confidence: 0,
+                observed_at: 1000,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_migrate_to_v1_translates_values() {
+    use crate::migrations::{
+        v1::{v0, MigrateToV1},
+        v4::v3,
+    };
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Roracle>();
+
+        // Write the single feed of the original layout, with an unbounded source
+        let old = |value: u128, status: &[u8]| v0::OracleValue {
+            value: value * 1_000_000_000_000u128,
+            source: vec![b'A'; 100],
+            updated_at: 1000,
+            status: status.to_vec(),
+        };
+        v0::History::<Test>::insert(0, old(49000, b"ok"));
+        v0::History::<Test>::insert(1, old(50000, b"fallback"));
+        v0::LastValue::<Test>::put(old(50000, b"fallback"));
+        v0::HistoryIndex::<Test>::put(2);
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV1::<Test, ConstU32<BTC_USD>>::pre_upgrade().unwrap();
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::post_upgrade(state).unwrap();
+
+        let last_value = v3::LastValue::<Test>::get(BTC_USD).unwrap();
+        assert_eq!(last_value.value, 50000 * 1_000_000_000_000u128);
+        assert_eq!(last_value.source, vec![b'A'; 64]);
+        assert_eq!(last_value.status, OracleStatus::Failover(1));
+        assert_eq!(v3::History::<Test>::get(BTC_USD, 1), Some(last_value));
+        assert_eq!(
+            v3::History::<Test>::get(BTC_USD, 0).unwrap().status,
+            OracleStatus::Primary
+        );
+        assert_eq!(v3::History::<Test>::iter_prefix(BTC_USD).count(), 2);
+        assert_eq!(Roracle::history_index(BTC_USD), 2);
+        assert!(v3::LastValue::<Test>::get(ETH_USD).is_none());
+
+        // Nothing is left in the old layout
+        assert!(!v0::LastValue::<Test>::exists());
+        assert!(!v0::HistoryIndex::<Test>::exists());
+        assert_eq!(StorageVersion::get::<Roracle>(), 1);
+    });
+}
+
+#[test]
+fn test_migrate_to_v2_adds_decimals() {
+    use crate::migrations::{
+        v2::{v1, MigrateToV2},
+        v5::v4,
+    };
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<Roracle>();
+        let old = v1::FeedConfig {
+            heartbeat: Some(60),
+            max_deviation: Some(Perbill::from_percent(5)),
+            deviation_window: 300,
+        };
+        frame_support::storage::unhashed::put(&FeedConfigs::<Test>::hashed_key_for(BTC_USD), &old);
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV2::<Test>::pre_upgrade().unwrap();
+        MigrateToV2::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV2::<Test>::post_upgrade(state).unwrap();
+
+        let expected = v4::FeedConfig {
+            heartbeat: Some(60),
+            max_deviation: Some(Perbill::from_percent(5)),
+            deviation_window: 300,
+            decimals: 12,
+        };
+        assert_eq!(
+            v4::FeedConfigs::<Test>::get(BTC_USD),
+            Some(expected.clone())
+        );
+        assert_eq!(StorageVersion::get::<Roracle>(), 2);
+
+        // Running again must not translate already migrated configs
+        MigrateToV2::<Test>::on_runtime_upgrade();
+        assert_eq!(v4::FeedConfigs::<Test>::get(BTC_USD), Some(expected));
+    });
+}
+
+#[test]
+fn test_migrate_to_v3_registers_existing_feeds() {
+    use crate::migrations::v3::MigrateToV3;
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(2).put::<Roracle>();
+        // BTC/USD has a configuration from before the registry, ETH/USD is already registered
+        Feeds::<Test>::remove(BTC_USD);
+        let eth = FeedInfo {
+            description: BoundedVec::truncate_from(b"Ether".to_vec()),
+            ..feed_info(b"ETH", b"USD")
+        };
+        Feeds::<Test>::insert(ETH_USD, &eth);
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV3::<Test>::pre_upgrade().unwrap();
+        MigrateToV3::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV3::<Test>::post_upgrade(state).unwrap();
+
+        let btc = Roracle::feed_info(BTC_USD).unwrap();
+        assert_eq!(btc.state, FeedState::Active);
+        assert!(btc.base.is_empty());
+        assert_eq!((btc.min_value, btc.max_value), (1, u128::MAX));
+        assert_eq!(Roracle::feed_info(ETH_USD), Some(eth));
+        assert_eq!(StorageVersion::get::<Roracle>(), 3);
+        assert_ok!(Roracle::do_try_state());
+    });
+}
+
+#[test]
+fn test_migrate_to_v1_runs_once() {
+    use crate::migrations::{
+        v1::{v0, MigrateToV1},
+        v4::v3,
+    };
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Roracle>();
+        let old = v0::OracleValue {
+            value: 1_000_000_000_000,
+            source: b"Binance".to_vec(),
+            updated_at: 1000,
+            status: b"ok".to_vec(),
+        };
+        v0::LastValue::<Test>::put(&old);
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV1::<Test, ConstU32<BTC_USD>>::pre_upgrade().unwrap();
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::post_upgrade(state).unwrap();
+
+        // Running again must not move already migrated values
+        let migrated = v3::LastValue::<Test>::get(BTC_USD).unwrap();
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV1::<Test, ConstU32<BTC_USD>>::pre_upgrade().unwrap();
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV1::<Test, ConstU32<BTC_USD>>::post_upgrade(state).unwrap();
+        assert_eq!(v3::LastValue::<Test>::get(BTC_USD), Some(migrated));
+        assert_eq!(StorageVersion::get::<Roracle>(), 1);
+    });
+}
+
+#[test]
+fn test_migrate_to_v4_adds_confidence() {
+    use crate::migrations::{
+        v4::{v3, MigrateToV4},
+        v5::v4,
+    };
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(3).put::<Roracle>();
+        let old = v3::OracleValue::<<Test as Config>::MaxSourceLen> {
+            value: 1_000_000_000_000,
+            source: BoundedVec::truncate_from(b"Binance".to_vec()),
+            updated_at: 1000,
+            status: OracleStatus::Primary,
+        };
+        v3::LastValue::<Test>::insert(BTC_USD, &old);
+        v3::History::<Test>::insert(BTC_USD, 0, &old);
+        HistoryIndex::<Test>::insert(BTC_USD, 1);
+        frame_support::storage::unhashed::put(
+            &Quarantine::<Test>::hashed_key_for(BTC_USD),
+            &v3::QuarantinedValue {
+                value: old.clone(),
+                deviation: Perbill::from_percent(20),
+                reporters: BoundedVec::<u64, <Test as Config>::MaxOperators>::truncate_from(vec![
+                    1,
+                ]),
+            },
+        );
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV4::<Test>::pre_upgrade().unwrap();
+        MigrateToV4::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV4::<Test>::post_upgrade(state).unwrap();
+
+        let expected = v4::OracleValue {
+            value: 1_000_000_000_000,
+            source: BoundedVec::truncate_from(b"Binance".to_vec()),
+            updated_at: 1000,
+            status: OracleStatus::Primary,
+            confidence: 0,
+        };
+        assert_eq!(v4::LastValue::<Test>::get(BTC_USD), Some(expected.clone()));
+        assert_eq!(v4::History::<Test>::get(BTC_USD, 0), Some(expected.clone()));
+        assert_eq!(
+            v4::Quarantine::<Test>::get(BTC_USD).unwrap().value,
+            expected
+        );
+        assert_eq!(StorageVersion::get::<Roracle>(), 4);
+    });
+}
+
+#[test]
+fn test_migrate_to_v5_adds_observation_times() {
+    use crate::migrations::{
+        v5::{v4, MigrateToV5},
+        v6::MigrateToV6,
+    };
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(4).put::<Roracle>();
+        let old = v4::OracleValue::<<Test as Config>::MaxSourceLen> {
+            value: 1_000_000_000_000,
+            source: BoundedVec::truncate_from(b"Binance".to_vec()),
+            updated_at: 900,
+            status: OracleStatus::Primary,
+            confidence: 5,
+        };
+        v4::LastValue::<Test>::insert(BTC_USD, &old);
+        v4::History::<Test>::insert(BTC_USD, 0, &old);
+        HistoryIndex::<Test>::insert(BTC_USD, 1);
+        v4::Quarantine::<Test>::insert(
+            BTC_USD,
+            v4::QuarantinedValueOf::<Test> {
+                value: old.clone(),
+                deviation: Perbill::from_percent(20),
+                reporters: BoundedVec::truncate_from(vec![1]),
+            },
+        );
+        v4::FeedConfigs::<Test>::insert(
+            BTC_USD,
+            v4::FeedConfig {
+                heartbeat: Some(60),
+                max_deviation: None,
+                deviation_window: 0,
+                decimals: 8,
+            },
+        );
+        v4::Rounds::<Test>::insert(
+            ETH_USD,
+            v4::RoundOf::<Test> {
+                id: 3,
+                deadline: 5,
+                submissions: BoundedVec::truncate_from(vec![v4::Submission {
+                    operator: 2,
+                    value: 3_000,
+                    source: BoundedVec::truncate_from(b"Kraken".to_vec()),
+                    status: OracleStatus::Primary,
+                }]),
+            },
+        );
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV5::<Test>::pre_upgrade().unwrap();
+        MigrateToV5::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV5::<Test>::post_upgrade(state).unwrap();
+
+        let expected = OracleValue {
+            value: 1_000_000_000_000,
+            source: BoundedVec::truncate_from(b"Binance".to_vec()),
+            updated_at: 900,
+            status: OracleStatus::Primary,
+            confidence: 5,
+            observed_at: 900,
+        };
+        assert_eq!(Roracle::last_value(BTC_USD), Some(expected.clone()));
+        assert_eq!(Roracle::history(BTC_USD, 0), Some(expected.clone()));
+        assert_eq!(Roracle::quarantined(BTC_USD).unwrap().value, expected);
+        assert_eq!(
+            Roracle::feed_config(BTC_USD),
+            Some(FeedConfig {
+                heartbeat: Some(60),
+                decimals: 8,
+                ..Default::default()
+            })
+        );
+        let round = Roracle::round(ETH_USD).unwrap();
+        assert_eq!(round.id, 3);
+        assert_eq!(round.submissions[0].observed_at, 1000);
+        assert_eq!(StorageVersion::get::<Roracle>(), 5);
+
+        // Open rounds are only indexed from version 6 on
+        MigrateToV6::<Test>::on_runtime_upgrade();
+        assert_ok!(Roracle::do_try_state());
+    });
+}
+
+#[test]
+fn test_migrate_to_v6_indexes_open_rounds() {
+    use crate::migrations::v6::MigrateToV6;
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(5).put::<Roracle>();
+        System::set_block_number(3);
+        let round = |id, deadline| Round::<Test> {
+            id,
+            deadline,
+            submissions: BoundedVec::truncate_from(vec![Submission {
+                operator: 2,
+                value: 3_000,
+                source: BoundedVec::truncate_from(b"Kraken".to_vec()),
+                status: OracleStatus::Primary,
+                observed_at: 1000,
+            }]),
+        };
+        Rounds::<Test>::insert(BTC_USD, round(0, 2));
+        Rounds::<Test>::insert(ETH_USD, round(4, 8));
+
+        #[cfg(feature = "try-runtime")]
+        let state = MigrateToV6::<Test>::pre_upgrade().unwrap();
+        MigrateToV6::<Test>::on_runtime_upgrade();
+        #[cfg(feature = "try-runtime")]
+        MigrateToV6::<Test>::post_upgrade(state).unwrap();
+
+        assert!(RoundDeadlines::<Test>::contains_key(8, ETH_USD));
+        // A round past its deadline closes in the upgrade block
+        assert_eq!(Roracle::round(BTC_USD).unwrap().deadline, 3);
+        assert!(RoundDeadlines::<Test>::contains_key(3, BTC_USD));
+        assert_eq!(StorageVersion::get::<Roracle>(), 6);
+        assert_ok!(Roracle::do_try_state());
+
+        Roracle::on_initialize(3);
+        assert!(Roracle::round(BTC_USD).is_none());
+        assert_eq!(Roracle::last_value(BTC_USD).unwrap().value, 3_000);
+        assert!(Roracle::round(ETH_USD).is_some());
+    });
+}
+
+#[test]
+fn test_history_is_pruned_beyond_max() {
+    new_test_ext().execute_with(|| {
+        for i in 1..=7u128 {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                i * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // Only the latest five entries are kept
+        assert_eq!(Roracle::history_index(BTC_USD), 7);
+        assert_eq!(Roracle::history_start(BTC_USD), 2);
+        assert!(Roracle::history(BTC_USD, 0).is_none());
+        assert!(Roracle::history(BTC_USD, 1).is_none());
+        assert_eq!(
+            Roracle::history(BTC_USD, 2).unwrap().value,
+            3 * 1_000_000_000_000u128
+        );
+        assert_eq!(
+            Roracle::history(BTC_USD, 6).unwrap().value,
+            7 * 1_000_000_000_000u128
+        );
+        assert_eq!(History::<Test>::iter_prefix(BTC_USD).count(), 5);
+
+        // Missing rounds are distinguishable from stored ones
+        assert!(Roracle::history(BTC_USD, 7).is_none());
+
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::HistoryPruned {
+                feed: BTC_USD,
+                removed: 1,
+                oldest: 2,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_history_shrinks_after_bound_lowered() {
+    new_test_ext().execute_with(|| {
+        // Simulate a history that outgrew the configured bound
+        for index in 0..8u64 {
+            History::<Test>::insert(
+                BTC_USD,
+                index,
+                OracleValue {
+                    value: 1,
+                    source: b"Binance".to_vec().try_into().unwrap(),
+                    updated_at: 1000,
+                    status: OracleStatus::Primary,
+                    confidence: 0,
+                    observed_at: 1000,
+                },
+            );
+        }
+        HistoryIndex::<Test>::insert(BTC_USD, 8);
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // At most two entries are pruned per insert
+        assert_eq!(Roracle::history_start(BTC_USD), 2);
+        assert_eq!(History::<Test>::iter_prefix(BTC_USD).count(), 7);
+    });
+}
+
+#[test]
+fn test_feed_goes_stale_and_recovers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedConfig {
+                heartbeat: Some(60),
+                ..Default::default()
+            }
+        ));
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // Within the heartbeat nothing happens
+        Now::set(1060);
+        System::set_block_number(2);
+        Roracle::on_finalize(2);
+        assert!(Roracle::stale_since(BTC_USD).is_none());
+        assert_eq!(Roracle::feed_status(BTC_USD), Some(OracleStatus::Primary));
+
+        // Missing the heartbeat marks the feed stale once
+        Now::set(1061);
+        System::set_block_number(3);
+        Roracle::on_finalize(3);
+        assert_eq!(Roracle::stale_since(BTC_USD), Some(1000));
+        assert_eq!(Roracle::feed_status(BTC_USD), Some(OracleStatus::Stale));
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedStale {
+                feed: BTC_USD,
+                last_update: 1000,
+            })
+            .into(),
+        );
+
+        // A new value recovers the feed
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            51000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert!(Roracle::stale_since(BTC_USD).is_none());
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedRecovered { feed: BTC_USD }).into(),
+        );
+    });
+}
+
+#[test]
+fn test_inactive_feeds_do_not_go_stale() {
+    new_test_ext().execute_with(|| {
+        for feed in [BTC_USD, ETH_USD] {
+            assert_ok!(Roracle::set_feed_config(
+                RuntimeOrigin::root(),
+                feed,
+                FeedConfig {
+                    heartbeat: Some(60),
+                    ..Default::default()
+                }
+            ));
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                feed,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedInfo {
+                state: FeedState::Paused,
+                ..feed_info(b"BTC", b"USD")
+            },
+            FeedConfig {
+                heartbeat: Some(60),
+                ..Default::default()
+            }
+        ));
+        assert_ok!(Roracle::deprecate_feed(RuntimeOrigin::root(), ETH_USD));
+
+        Now::set(1061);
+        System::set_block_number(2);
+        Roracle::on_finalize(2);
+        assert!(!Roracle::is_stale(BTC_USD));
+        assert!(!Roracle::is_stale(ETH_USD));
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedStale { .. })
+        )));
+    });
+}
+
+#[test]
+fn test_heartbeat_checks_continue_in_next_block() {
+    new_test_ext().execute_with(|| {
+        MaxHeartbeatChecks::set(1);
+        for feed in [BTC_USD, ETH_USD] {
+            assert_ok!(Roracle::set_feed_config(
+                RuntimeOrigin::root(),
+                feed,
+                FeedConfig {
+                    heartbeat: Some(60),
+                    ..Default::default()
+                }
+            ));
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                feed,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // One feed is checked per block, the other one in the next block
+        Now::set(1061);
+        System::set_block_number(2);
+        Roracle::on_finalize(2);
+        let first = pallet_roracle::HeartbeatCursor::<Test>::get().unwrap();
+        let second = if first == BTC_USD { ETH_USD } else { BTC_USD };
+        assert!(Roracle::is_stale(first));
+        assert!(!Roracle::is_stale(second));
+
+        System::set_block_number(3);
+        Roracle::on_finalize(3);
+        assert!(Roracle::is_stale(second));
+        assert!(pallet_roracle::HeartbeatCursor::<Test>::get().is_none());
+    });
+}
+
+#[test]
+fn test_get_fresh_value() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Roracle::get_fresh_value(BTC_USD, 60),
+            pallet_roracle::Error::<Test>::NoValue
+        );
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        Now::set(1060);
+        assert_eq!(
+            Roracle::get_fresh_value(BTC_USD, 60).unwrap().value,
+            50000 * 1_000_000_000_000u128
+        );
+
+        Now::set(1061);
+        assert_err!(
+            Roracle::get_fresh_value(BTC_USD, 60),
+            pallet_roracle::Error::<Test>::StaleValue
+        );
+    });
+}
+
+#[test]
+fn test_set_feed_config_requires_admin() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Roracle::set_feed_config(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                FeedConfig {
+                    heartbeat: Some(60),
+                    ..Default::default()
+                }
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_eq!(Roracle::feed_config(BTC_USD), Some(FeedConfig::default()));
+    });
+}
+
+#[test]
+fn test_set_feed_config_rejects_invalid_decimals() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Roracle::set_feed_config(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                FeedConfig {
+                    decimals: MAX_DECIMALS + 1,
+                    ..Default::default()
+                }
+            ),
+            Error::<Test>::InvalidDecimals
+        );
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedConfig {
+                decimals: MAX_DECIMALS,
+                ..Default::default()
+            }
+        ));
+        assert_eq!(
+            Roracle::feed_config(BTC_USD).unwrap().decimals,
+            MAX_DECIMALS
+        );
+    });
+}
+
+#[test]
+fn test_decimals_locked_once_feed_has_values() {
+    new_test_ext().execute_with(|| {
+        let config = |decimals| FeedConfig {
+            decimals,
+            ..Default::default()
+        };
+
+        // An open round holds submissions in the current decimals
+        MinQuorum::set(2);
+        assert_ok!(submit_observed(1, 50000, None));
+        assert_err!(
+            Roracle::set_feed_config(RuntimeOrigin::root(), BTC_USD, config(8)),
+            Error::<Test>::DecimalsLocked
+        );
+        assert_ok!(submit_observed(2, 50000, None));
+        assert!(Roracle::last_value(BTC_USD).is_some());
+
+        assert_err!(
+            Roracle::set_feed_config(RuntimeOrigin::root(), BTC_USD, config(8)),
+            Error::<Test>::DecimalsLocked
+        );
+        assert_err!(
+            Roracle::update_feed(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                feed_info(b"BTC", b"USD"),
+                config(8)
+            ),
+            Error::<Test>::DecimalsLocked
+        );
+        assert_eq!(Roracle::decimals(BTC_USD), 12);
+
+        // Other settings can still change
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedConfig {
+                heartbeat: Some(60),
+                ..config(12)
+            }
+        ));
+        // Feeds without values are free to change
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            ETH_USD,
+            config(8)
+        ));
+        assert_eq!(Roracle::decimals(ETH_USD), 8);
+    });
+}
+
+#[test]
+fn test_fixed_point_conversions() {
+    use crate::fixed::{from_fixed, rescale, to_fixed};
+    use sp_runtime::FixedU128;
+
+    assert_eq!(
+        rescale(43_250_120_000_000_000, 12, 8),
+        Some(4_325_012_000_000)
+    );
+    assert_eq!(
+        rescale(4_325_012_000_000, 8, 12),
+        Some(43_250_120_000_000_000)
+    );
+    assert_eq!(rescale(123_456, 3, 0), Some(123));
+    assert_eq!(rescale(7, 5, 5), Some(7));
+    // Dropping every digit leaves zero, growing beyond a u128 fails
+    assert_eq!(rescale(u128::MAX, 38, 0), Some(3));
+    assert_eq!(rescale(u128::MAX, 60, 0), Some(0));
+    assert_eq!(rescale(u128::MAX, 0, 1), None);
+
+    let price = FixedU128::from_inner(43_250_120_000_000_000_000_000);
+    assert_eq!(to_fixed(43_250_120_000_000_000, 12), Some(price));
+    assert_eq!(to_fixed(1_500, 3), Some(FixedU128::from_rational(3, 2)));
+    assert_eq!(to_fixed(u128::MAX, 12), None);
+    assert_eq!(
+        from_fixed(FixedU128::from_rational(3, 2), 8),
+        Some(150_000_000)
+    );
+}
+
+#[test]
+fn test_fixed_value_uses_feed_decimals() {
+    new_test_ext().execute_with(|| {
+        use sp_runtime::FixedU128;
+
+        assert_eq!(Roracle::decimals(BTC_USD), 12);
+        // Feeds without configuration use the decimals of the offchain worker
+        assert_eq!(Roracle::decimals(7), 12);
+        assert!(Roracle::fixed_value(BTC_USD).is_none());
+
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedConfig {
+                decimals: 8,
+                ..Default::default()
+            }
+        ));
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            4_325_012_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_eq!(Roracle::decimals(BTC_USD), 8);
+        assert_eq!(
+            Roracle::fixed_value(BTC_USD),
+            Some(FixedU128::from_inner(43_250_120_000_000_000_000_000))
+        );
+    });
+}
+
+fn publish_at(now: u64, value: u128) {
+    Now::set(now);
+    assert_ok!(Roracle::submit_oracle_value(
+        RuntimeOrigin::signed(1),
+        BTC_USD,
+        value,
+        b"Binance".to_vec(),
+        OracleStatus::Primary,
+        None
+    ));
+}
+
+#[test]
+fn test_twap_over_windows() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Roracle::twap(BTC_USD, 60), None);
+
+        publish_at(1000, 100);
+        publish_at(1120, 200);
+        // Boundaries passed since the first value hold the accumulator at that time
+        assert_eq!(
+            Roracle::twap_observation(BTC_USD, 17 % 5),
+            Some(Observation {
+                timestamp: 1020,
+                cumulative: 2_000
+            })
+        );
+        assert_eq!(
+            Roracle::twap_observation(BTC_USD, 18 % 5),
+            Some(Observation {
+                timestamp: 1080,
+                cumulative: 8_000
+            })
+        );
+
+        Now::set(1180);
+        // 40 seconds at 100 since the 1080 boundary, then 60 seconds at 200
+        assert_eq!(Roracle::twap(BTC_USD, 100), Some(160));
+        // Windows starting after the last value only see its price
+        assert_eq!(Roracle::twap(BTC_USD, 30), Some(200));
+        // Nothing was accumulated before the first value
+        assert_eq!(Roracle::twap(BTC_USD, 1000), None);
+        assert_eq!(Roracle::twap(ETH_USD, 100), None);
+    });
+}
+
+#[test]
+fn test_twap_after_gap_keeps_latest_boundaries() {
+    new_test_ext().execute_with(|| {
+        publish_at(1000, 100);
+        publish_at(1120, 200);
+        publish_at(1500, 300);
+
+        // The boundaries from 1260 to 1500 were filled in at once
+        assert_eq!(
+            Roracle::twap_observation(BTC_USD, 21 % 5).map(|o| o.timestamp),
+            Some(1260)
+        );
+        assert_eq!(Roracle::twap(BTC_USD, 240), Some(200));
+        // The 1200 boundary was overwritten by the 1500 one
+        assert_eq!(Roracle::twap(BTC_USD, 300), None);
+
+        let accumulator = Roracle::price_accumulator(BTC_USD).unwrap();
+        assert_eq!(accumulator.cumulative, 100 * 120 + 200 * 380);
+        assert_eq!(
+            (accumulator.last_value, accumulator.last_update),
+            (300, 1500)
+        );
+    });
+}
+
+fn candle(start: u64, open: u128, high: u128, low: u128, close: u128, count: u32) -> Candle {
+    Candle {
+        start,
+        open,
+        high,
+        low,
+        close,
+        count,
+    }
+}
+
+#[test]
+fn test_candles_per_interval() {
+    new_test_ext().execute_with(|| {
+        publish_at(1000, 100);
+        publish_at(1010, 150);
+        publish_at(1030, 80);
+        publish_at(1070, 120);
+
+        assert_eq!(
+            Roracle::candles(BTC_USD, 60, 0, u64::MAX),
+            vec![
+                candle(960, 100, 150, 100, 150, 2),
+                candle(1020, 80, 120, 80, 120, 2)
+            ]
+        );
+        assert_eq!(
+            Roracle::candles(BTC_USD, 3600, 0, u64::MAX),
+            vec![candle(0, 100, 150, 80, 120, 4)]
+        );
+        // Candles are selected by their start
+        assert_eq!(
+            Roracle::candles(BTC_USD, 60, 961, u64::MAX),
+            vec![candle(1020, 80, 120, 80, 120, 2)]
+        );
+        assert_eq!(
+            Roracle::candles(BTC_USD, 60, 0, 1019),
+            vec![candle(960, 100, 150, 100, 150, 2)]
+        );
+        // Only configured intervals are kept
+        assert!(Roracle::candles(BTC_USD, 120, 0, u64::MAX).is_empty());
+        assert!(Roracle::candles(ETH_USD, 60, 0, u64::MAX).is_empty());
+
+        assert_ok!(Roracle::do_try_state());
+    });
+}
+
+#[test]
+fn test_candles_retention() {
+    new_test_ext().execute_with(|| {
+        publish_at(1000, 100);
+        publish_at(1070, 120);
+        // Three minutes later the slot of the 1020 candle is reused
+        publish_at(1200, 90);
+
+        assert_eq!(
+            Roracle::candle((BTC_USD, 60, 2)),
+            Some(candle(1200, 90, 90, 90, 90, 1))
+        );
+        // The 960 candle is still stored but no longer retained
+        assert!(Roracle::candle((BTC_USD, 60, 1)).is_some());
+        assert_eq!(
+            Roracle::candles(BTC_USD, 60, 0, u64::MAX),
+            vec![candle(1200, 90, 90, 90, 90, 1)]
+        );
+    });
+}
+
+#[test]
+fn test_price_provider() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(<Roracle as PriceProvider<u32>>::get(&BTC_USD), None);
+
+        publish_at(1000, 100);
+        assert_eq!(<Roracle as PriceProvider<u32>>::get(&BTC_USD), Some(100));
+        assert_eq!(
+            <Roracle as PriceProvider<u32>>::get_with_timestamp(&BTC_USD),
+            Some((100, 1000))
+        );
+
+        Now::set(1060);
+        assert_eq!(
+            <Roracle as PriceProvider<u32>>::get_no_older_than(&BTC_USD, 60),
+            Some(100)
+        );
+        assert_eq!(
+            <Roracle as PriceProvider<u32>>::get_no_older_than(&BTC_USD, 59),
+            None
+        );
+
+        // Values of paused feeds are withheld
+        let paused = FeedInfo {
+            state: FeedState::Paused,
+            ..feed_info(b"BTC", b"USD")
+        };
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            paused,
+            FeedConfig::default()
+        ));
+        assert_eq!(<Roracle as PriceProvider<u32>>::get(&BTC_USD), None);
+        assert_eq!(
+            <Roracle as PriceProvider<u32>>::get_no_older_than(&BTC_USD, 60),
+            None
+        );
+    });
+}
+
+#[test]
+fn test_mock_price_provider() {
+    MockPriceProvider::reset();
+    assert_eq!(<() as PriceProvider<u32>>::get(&BTC_USD), None);
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get(&BTC_USD),
+        None
+    );
+
+    MockPriceProvider::set_price(&BTC_USD, 100, 1000);
+    MockPriceProvider::set_now(1030);
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get_with_timestamp(&BTC_USD),
+        Some((100, 1000))
+    );
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get_no_older_than(&BTC_USD, 30),
+        Some(100)
+    );
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get_no_older_than(&BTC_USD, 29),
+        None
+    );
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get(&ETH_USD),
+        None
+    );
+
+    MockPriceProvider::remove_price(&BTC_USD);
+    assert_eq!(
+        <MockPriceProvider as PriceProvider<u32>>::get(&BTC_USD),
+        None
+    );
+}
+
+#[test]
+fn test_on_new_value_notified_of_published_values() {
+    new_test_ext().execute_with(|| {
+        NewValues::take();
+        publish_at(1000, 100);
+        publish_at(1010, 150);
+        assert_eq!(
+            NewValues::get(),
+            vec![(BTC_USD, None, 100), (BTC_USD, Some(100), 150)]
+        );
+
+        // Values held back by the circuit breaker are only reported once confirmed
+        let config = FeedConfig {
+            max_deviation: Some(Perbill::from_percent(10)),
+            deviation_window: 600,
+            ..Default::default()
+        };
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            config
+        ));
+        publish_at(1020, 300);
+        assert!(Roracle::quarantined(BTC_USD).is_some());
+        assert_eq!(NewValues::get().len(), 2);
+
+        assert_ok!(Roracle::confirm_quarantined(RuntimeOrigin::root(), BTC_USD));
+        assert_eq!(NewValues::get().last(), Some(&(BTC_USD, Some(150), 300)));
+    });
+}
+
+#[test]
+fn test_on_new_value_weight_is_charged() {
+    let call = pallet_roracle::Call::<Test>::submit_oracle_value {
+        feed: BTC_USD,
+        value: 100,
+        source: b"Binance".to_vec(),
+        status: OracleStatus::Primary,
+        observed_at: None,
+    };
+    assert_eq!(
+        call.get_dispatch_info().weight,
+        <() as WeightInfo>::submit_oracle_value(7).saturating_add(Weight::from_parts(1_000, 0))
+    );
+
+    let call = pallet_roracle::Call::<Test>::confirm_quarantined { feed: BTC_USD };
+    assert_eq!(
+        call.get_dispatch_info().weight,
+        <() as WeightInfo>::confirm_quarantined().saturating_add(Weight::from_parts(1_000, 0))
+    );
+}
+
+#[test]
+fn test_submit_batch() {
+    new_test_ext().execute_with(|| {
+        let values = BoundedVec::truncate_from(vec![
+            (BTC_USD, 43_000, OracleStatus::Primary),
+            (7, 1_000, OracleStatus::Primary),
+            (ETH_USD, 0, OracleStatus::Primary),
+        ]);
+        assert_ok!(Roracle::submit_batch(
+            RuntimeOrigin::signed(1),
+            values,
+            b"Binance".to_vec(),
+            None
+        ));
+        system::Pallet::<Test>::assert_last_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::BatchSubmitted {
+                who: 1,
+                results: vec![
+                    (BTC_USD, Ok(())),
+                    (7, Err(Error::<Test>::UnknownFeed.into())),
+                    (ETH_USD, Err(Error::<Test>::InvalidValue.into())),
+                ],
+            })
+            .into(),
+        );
+        // Rejected values do not affect accepted ones
+        assert_eq!(
+            Roracle::last_value(BTC_USD).map(|value| value.value),
+            Some(43_000)
+        );
+        assert!(Roracle::last_value(ETH_USD).is_none());
+    });
+}
+
+#[test]
+fn test_submit_batch_requires_operator() {
+    new_test_ext().execute_with(|| {
+        let values = BoundedVec::truncate_from(vec![(BTC_USD, 43_000, OracleStatus::Primary)]);
+        assert_err!(
+            Roracle::submit_batch(RuntimeOrigin::signed(9), values, b"Binance".to_vec(), None),
+            Error::<Test>::NotOperator
+        );
+    });
+}
+
+#[test]
+fn test_submit_batch_weight_is_linear() {
+    let weight = |n: usize| {
+        let values = BoundedVec::truncate_from(vec![(BTC_USD, 1, OracleStatus::Primary); n]);
+        let source = b"Binance".to_vec();
+        pallet_roracle::Call::<Test>::submit_batch {
+            values,
+            source,
+            observed_at: None,
+        }
+        .get_dispatch_info()
+        .weight
+    };
+    let hook = Weight::from_parts(1_000, 0);
+    assert_eq!(
+        weight(1),
+        <() as WeightInfo>::submit_batch(1, 7).saturating_add(hook)
+    );
+    assert_eq!(
+        weight(3),
+        <() as WeightInfo>::submit_batch(3, 7).saturating_add(hook.saturating_mul(3))
+    );
+    assert!(weight(3).all_gt(weight(1)));
+}
+
+fn submit_observed(who: u64, value: u128, observed_at: Option<u64>) -> DispatchResult {
+    Roracle::submit_oracle_value(
+        RuntimeOrigin::signed(who),
+        BTC_USD,
+        value * 1_000_000_000_000u128,
+        b"Binance".to_vec(),
+        OracleStatus::Primary,
+        observed_at,
+    )
+}
+
+#[test]
+fn test_observation_time_is_validated() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            submit_observed(1, 50000, Some(1001)),
+            Error::<Test>::ObservationInFuture
+        );
+
+        assert_ok!(submit_observed(1, 50000, Some(990)));
+        let last = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!((last.updated_at, last.observed_at), (1000, 990));
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::ValueUpdated {
+                feed: BTC_USD,
+                value: 50000 * 1_000_000_000_000u128,
+                source: b"Binance".to_vec().try_into().unwrap(),
+                updated_at: 1000,
+                status: OracleStatus::Primary,
+                confidence: 0,
+                observed_at: 990,
+            })
+            .into(),
+        );
+
+        // Observations cannot go back before the published value
+        assert_err!(
+            submit_observed(1, 50000, Some(980)),
+            Error::<Test>::ObservationOutOfOrder
+        );
+
+        // Submissions without an observation time are observed at the current time
+        assert_ok!(submit_observed(1, 50000, None));
+        assert_eq!(Roracle::last_value(BTC_USD).unwrap().observed_at, 1000);
+
+        assert_ok!(Roracle::set_feed_config(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedConfig {
+                max_observation_age: Some(30),
+                ..Default::default()
+            }
+        ));
+        Now::set(1100);
+        assert_err!(
+            submit_observed(1, 50000, Some(1069)),
+            Error::<Test>::ObservationTooOld
+        );
+        assert_ok!(submit_observed(1, 50000, Some(1070)));
+        assert_eq!(Roracle::last_value(BTC_USD).unwrap().observed_at, 1070);
+    });
+}
+
+#[test]
+fn test_round_overtaken_by_newer_value_is_discarded() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 20, 300);
+        assert_ok!(submit_observed(1, 50000, None));
+        Now::set(1010);
+        assert_ok!(submit_observed(1, 25000, None));
+        assert_eq!(
+            Roracle::quarantined(BTC_USD).unwrap().value.observed_at,
+            1010
+        );
+
+        // A round observed before the quarantined value is still open when it gets confirmed
+        MinQuorum::set(3);
+        assert_ok!(submit_observed(1, 50000, Some(1005)));
+        assert_ok!(submit_observed(2, 50000, Some(1005)));
+        assert_ok!(Roracle::confirm_quarantined(RuntimeOrigin::root(), BTC_USD));
+        assert_ok!(submit_observed(3, 50001, None));
+
+        let last = Roracle::last_value(BTC_USD).unwrap();
+        assert_eq!(
+            (last.value, last.observed_at),
+            (25000 * 1_000_000_000_000u128, 1010)
+        );
+        assert!(Roracle::round(BTC_USD).is_none());
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::RoundOutOfOrder {
+                feed: BTC_USD,
+                round: 2,
+                observed_at: 1005,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_create_feed() {
+    new_test_ext().execute_with(|| {
+        const SOL_USD: u32 = 2;
+        let info = feed_info(b"SOL", b"USD");
+        let config = FeedConfig {
+            heartbeat: Some(60),
+            decimals: 8,
+            ..Default::default()
+        };
+
+        assert_err!(
+            Roracle::create_feed(
+                RuntimeOrigin::signed(1),
+                SOL_USD,
+                info.clone(),
+                config.clone()
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_err!(
+            Roracle::create_feed(
+                RuntimeOrigin::root(),
+                SOL_USD,
+                FeedInfo {
+                    base: Default::default(),
+                    ..info.clone()
+                },
+                config.clone()
+            ),
+            Error::<Test>::InvalidSymbol
+        );
+        assert_err!(
+            Roracle::create_feed(
+                RuntimeOrigin::root(),
+                SOL_USD,
+                FeedInfo {
+                    min_value: 10,
+                    max_value: 5,
+                    ..info.clone()
+                },
+                config.clone()
+            ),
+            Error::<Test>::InvalidBounds
+        );
+        assert_err!(
+            Roracle::create_feed(
+                RuntimeOrigin::root(),
+                SOL_USD,
+                FeedInfo {
+                    state: FeedState::Deprecated,
+                    ..info.clone()
+                },
+                config.clone()
+            ),
+            Error::<Test>::FeedDeprecated
+        );
+
+        assert_ok!(Roracle::create_feed(
+            RuntimeOrigin::root(),
+            SOL_USD,
+            info.clone(),
+            config.clone()
+        ));
+        assert_eq!(Roracle::feed_info(SOL_USD), Some(info.clone()));
+        assert_eq!(Roracle::feed_config(SOL_USD), Some(config.clone()));
+        system::Pallet::<Test>::assert_last_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedCreated { feed: SOL_USD }).into(),
+        );
+
+        assert_err!(
+            Roracle::create_feed(RuntimeOrigin::root(), SOL_USD, info, config),
+            Error::<Test>::FeedAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn test_unknown_feed_rejects_submissions() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                7,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            Error::<Test>::UnknownFeed
+        );
+        assert_err!(
+            Roracle::set_feed_config(RuntimeOrigin::root(), 7, FeedConfig::default()),
+            Error::<Test>::UnknownFeed
+        );
+        assert_err!(
+            Roracle::set_feed_sources(RuntimeOrigin::root(), 7, Default::default()),
+            Error::<Test>::UnknownFeed
+        );
+    });
+}
+
+#[test]
+fn test_paused_feed_rejects_submissions() {
+    new_test_ext().execute_with(|| {
+        let paused = FeedInfo {
+            state: FeedState::Paused,
+            ..feed_info(b"BTC", b"USD")
+        };
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            paused,
+            FeedConfig::default()
+        ));
+        system::Pallet::<Test>::assert_last_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedUpdated {
+                feed: BTC_USD,
+                state: FeedState::Paused,
+            })
+            .into(),
+        );
+        assert!(!Roracle::is_active(BTC_USD));
+
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            Error::<Test>::FeedNotActive
+        );
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Call.into()
+        );
+
+        // Resuming the feed accepts submissions again
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            feed_info(b"BTC", b"USD"),
+            FeedConfig::default()
+        ));
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+    });
+}
+
+#[test]
+fn test_submission_outside_feed_bounds_rejected() {
+    new_test_ext().execute_with(|| {
+        let bounded = FeedInfo {
+            min_value: 1_000_000_000_000,
+            max_value: 1_000_000_000_000_000_000,
+            ..feed_info(b"BTC", b"USD")
+        };
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            bounded,
+            FeedConfig::default()
+        ));
+
+        for value in [999_999_999_999, 1_000_000_000_000_000_001] {
+            assert_err!(
+                Roracle::submit_oracle_value(
+                    RuntimeOrigin::signed(1),
+                    BTC_USD,
+                    value,
+                    b"Binance".to_vec(),
+                    OracleStatus::Primary,
+                    None
+                ),
+                Error::<Test>::ValueOutOfBounds
+            );
+        }
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+    });
+}
+
+#[test]
+fn test_deprecate_feed() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_ok!(Roracle::set_feed_sources(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            BoundedVec::truncate_from(btc_sources())
+        ));
+        assert!(Roracle::round(BTC_USD).is_some());
+
+        assert_err!(
+            Roracle::deprecate_feed(RuntimeOrigin::signed(1), BTC_USD),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Roracle::deprecate_feed(RuntimeOrigin::root(), BTC_USD));
+        system::Pallet::<Test>::assert_last_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::FeedDeprecated { feed: BTC_USD }).into(),
+        );
+        assert_eq!(
+            Roracle::feed_info(BTC_USD).unwrap().state,
+            FeedState::Deprecated
+        );
+        assert!(Roracle::round(BTC_USD).is_none());
+        assert!(!RoundDeadlines::<Test>::contains_key(6, BTC_USD));
+        assert!(Roracle::feed_sources(BTC_USD).is_empty());
+
+        assert_err!(
+            Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(2),
+                BTC_USD,
+                1_000_000_000_000,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ),
+            Error::<Test>::FeedNotActive
+        );
+        // Deprecation is final
+        assert_err!(
+            Roracle::update_feed(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                feed_info(b"BTC", b"USD"),
+                FeedConfig::default()
+            ),
+            Error::<Test>::FeedDeprecated
+        );
+        assert_err!(
+            Roracle::deprecate_feed(RuntimeOrigin::root(), BTC_USD),
+            Error::<Test>::FeedDeprecated
+        );
+        assert_err!(
+            Roracle::deprecate_feed(RuntimeOrigin::root(), 7),
+            Error::<Test>::UnknownFeed
+        );
+    });
+}
+
+fn set_max_deviation(feed: u32, percent: u32, window: u64) {
+    assert_ok!(Roracle::set_feed_config(
+        RuntimeOrigin::root(),
+        feed,
+        FeedConfig {
+            max_deviation: Some(Perbill::from_percent(percent)),
+            deviation_window: window,
+            ..Default::default()
+        }
+    ));
+}
+
+#[test]
+fn test_circuit_breaker_quarantines_outlier() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 20, 300);
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        // A 19% move is accepted
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            59500 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            59500 * 1_000_000_000_000u128
+        );
+
+        // A 50% drop is held back
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            29750 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            59500 * 1_000_000_000_000u128
+        );
+        assert_eq!(Roracle::history_index(BTC_USD), 2);
+
+        let quarantined = Roracle::quarantined(BTC_USD).unwrap();
+        assert_eq!(quarantined.value.value, 29750 * 1_000_000_000_000u128);
+        assert_eq!(quarantined.reporters.into_inner(), vec![1]);
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::CircuitBreakerTripped {
+                feed: BTC_USD,
+                last: 59500 * 1_000_000_000_000u128,
+                proposed: 29750 * 1_000_000_000_000u128,
+                deviation: Perbill::from_percent(50),
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_circuit_breaker_trips_beyond_full_deviation() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 100, 300);
+        assert_ok!(submit_observed(1, 50000, None));
+
+        // Doubling is a 100% move and still accepted
+        assert_ok!(submit_observed(1, 100000, None));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            100000 * 1_000_000_000_000u128
+        );
+
+        // A 1000x jump is held back, reported as the largest deviation
+        assert_ok!(submit_observed(1, 100000 * 1000, None));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            100000 * 1_000_000_000_000u128
+        );
+        let quarantined = Roracle::quarantined(BTC_USD).unwrap();
+        assert_eq!(
+            quarantined.value.value,
+            100000 * 1000 * 1_000_000_000_000u128
+        );
+        assert_eq!(quarantined.deviation, Perbill::one());
+    });
+}
+
+#[test]
+fn test_circuit_breaker_only_applies_within_window() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 20, 300);
+
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            50000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+
+        Now::set(1301);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            25000 * 1_000_000_000_000u128,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            25000 * 1_000_000_000_000u128
+        );
+        assert!(Roracle::quarantined(BTC_USD).is_none());
+    });
+}
+
+#[test]
+fn test_quarantined_value_confirmation() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 20, 300);
+
+        for value in [50000u128, 25000] {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                value * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // The reporting operator cannot confirm its own value
+        assert_err!(
+            Roracle::confirm_quarantined(RuntimeOrigin::signed(1), BTC_USD),
+            pallet_roracle::Error::<Test>::CannotConfirmOwnValue
+        );
+        // Non-operators cannot confirm
+        assert_err!(
+            Roracle::confirm_quarantined(RuntimeOrigin::signed(4), BTC_USD),
+            pallet_roracle::Error::<Test>::NotOperator
+        );
+
+        // A second operator confirms
+        assert_ok!(Roracle::confirm_quarantined(
+            RuntimeOrigin::signed(2),
+            BTC_USD
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            25000 * 1_000_000_000_000u128
+        );
+        assert!(Roracle::quarantined(BTC_USD).is_none());
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::CircuitBreakerOverridden {
+                feed: BTC_USD,
+                value: 25000 * 1_000_000_000_000u128,
+                confirmed_by: Some(2),
+            })
+            .into(),
+        );
+
+        assert_err!(
+            Roracle::confirm_quarantined(RuntimeOrigin::root(), BTC_USD),
+            pallet_roracle::Error::<Test>::NotQuarantined
+        );
+    });
+}
+
+#[test]
+fn test_admin_overrides_circuit_breaker() {
+    new_test_ext().execute_with(|| {
+        set_max_deviation(BTC_USD, 20, 300);
+
+        for value in [50000u128, 80000] {
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                value * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        assert_ok!(Roracle::confirm_quarantined(RuntimeOrigin::root(), BTC_USD));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            80000 * 1_000_000_000_000u128
+        );
+        system::Pallet::<Test>::assert_has_event(
+            RuntimeEvent::Roracle(pallet_roracle::Event::CircuitBreakerOverridden {
+                feed: BTC_USD,
+                value: 80000 * 1_000_000_000_000u128,
+                confirmed_by: None,
+            })
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn test_history_queries() {
+    new_test_ext().execute_with(|| {
+        // Seven values at 10 second intervals, of which the last five are retained
+        for i in 0..7u64 {
+            Now::set(1000 + i * 10);
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                (i as u128 + 1) * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+
+        // Index ranges are clamped to retained entries
+        let range: Vec<u64> = Roracle::history_range(BTC_USD, 0, 4)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(range, vec![2, 3]);
+        assert_eq!(Roracle::history_range(BTC_USD, 5, 100).len(), 2);
+        assert!(Roracle::history_range(ETH_USD, 0, 100).is_empty());
+
+        // Timestamp ranges are inclusive on both ends
+        let by_time: Vec<u64> = Roracle::history_by_time(BTC_USD, 1030, 1050)
+            .into_iter()
+            .map(|(_, value)| value.updated_at)
+            .collect();
+        assert_eq!(by_time, vec![1030, 1040, 1050]);
+        assert_eq!(Roracle::history_by_time(BTC_USD, 0, 1025).len(), 1);
+        assert!(Roracle::history_by_time(BTC_USD, 1061, 2000).is_empty());
+
+        assert_eq!(Roracle::feeds(), vec![BTC_USD]);
+        assert!(!Roracle::is_stale(BTC_USD));
+    });
+}
+
+const BINANCE_URL: &str = "https://api.binance.com/api/v3/ticker/price?symbol=BTCUSDT";
+const COINBASE_URL: &str = "https://api.coinbase.com/v2/prices/BTC-USD/spot";
+const KRAKEN_URL: &str = "https://api.kraken.com/0/public/Ticker?pair=XBTUSD";
+
+fn price_source(name: &str, url: &str, format: PriceFormat) -> PriceSourceOf<Test> {
+    PriceSource {
+        name: name.as_bytes().to_vec().try_into().unwrap(),
+        url: url.as_bytes().to_vec().try_into().unwrap(),
+        format,
+    }
+}
+
+fn btc_sources() -> Vec<PriceSourceOf<Test>> {
+    vec![
+        price_source("Binance", BINANCE_URL, PriceFormat::Binance),
+        price_source("Coinbase", COINBASE_URL, PriceFormat::Coinbase),
+        price_source("Kraken", KRAKEN_URL, PriceFormat::Kraken),
+    ]
+}
+
+fn http_response(url: &str, body: &str) -> testing::PendingRequest {
+    testing::PendingRequest {
+        method: "GET".into(),
+        uri: url.into(),
+        response: Some(body.as_bytes().to_vec()),
+        sent: true,
+        ..Default::default()
+    }
+}
+
+/// Run `test` with the given mocked HTTP responses and return the transactions it submitted
+fn with_offchain(responses: Vec<testing::PendingRequest>, test: impl FnOnce()) -> Vec<Extrinsic> {
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    for response in responses {
+        offchain_state.write().expect_request(response);
+    }
+
+    let mut ext = new_test_ext();
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.execute_with(test);
+
+    let transactions = pool_state
+        .read()
+        .transactions
+        .iter()
+        .map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap())
+        .collect();
+    transactions
+}
+
+/// Signed payload of an unsigned submission, checking its signature
+fn submitted_payload(tx: &Extrinsic) -> OraclePayloadOf<Test> {
+    assert_eq!(tx.signature, None);
+    let RuntimeCall::Roracle(crate::Call::submit_oracle_value_unsigned_with_signed_payload {
+        payload,
+        signature,
+    }) = &tx.call
+    else {
+        panic!("unexpected call {:?}", tx.call)
+    };
+    assert!(payload.public.verify(&payload.encode(), signature));
+    payload.clone()
+}
+
+#[test]
+fn test_parse_price_formats() {
+    let price = 43_250_120_000_000_000u128; // 43250.12 with 12 decimals
+
+    assert_eq!(
+        offchain::parse_price(
+            PriceFormat::Binance,
+            br#"{"symbol":"BTCUSDT","price":"43250.12000000"}"#
+        ),
+        Some(price)
+    );
+    assert_eq!(
+        offchain::parse_price(
+            PriceFormat::Coinbase,
+            br#"{"data":{"amount":"43250.12","base":"BTC","currency":"USD"}}"#
+        ),
+        Some(price)
+    );
+    assert_eq!(
+        offchain::parse_price(
+            PriceFormat::Kraken,
+            br#"{"error":[],"result":{"XXBTZUSD":{"a":["43251.0","1","1.000"],"c":["43250.12","0.01"]}}}"#
+        ),
+        Some(price)
+    );
+
+    // Wrong layout, numbers instead of strings and malformed JSON are rejected
+    assert_eq!(
+        offchain::parse_price(PriceFormat::Coinbase, br#"{"price":"1.0"}"#),
+        None
+    );
+    assert_eq!(
+        offchain::parse_price(PriceFormat::Binance, br#"{"price":1.0}"#),
+        None
+    );
+    assert_eq!(offchain::parse_price(PriceFormat::Binance, b"<html>"), None);
+
+    // Fraction digits beyond the submitted precision are truncated
+    let chars: Vec<char> = "1.1234567890129".chars().collect();
+    assert_eq!(offchain::parse_decimal(&chars), Some(1_123_456_789_012));
+    for invalid in ["", ".", "-1", "1.2.3", "1e5"] {
+        let chars: Vec<char> = invalid.chars().collect();
+        assert_eq!(offchain::parse_decimal(&chars), None);
+    }
+}
+
+#[test]
+fn test_set_feed_sources() {
+    new_test_ext().execute_with(|| {
+        let sources: BoundedVec<_, _> = btc_sources().try_into().unwrap();
+
+        assert_err!(
+            Roracle::set_feed_sources(RuntimeOrigin::signed(1), BTC_USD, sources.clone()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(Roracle::set_feed_sources(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            sources.clone()
+        ));
+        assert_eq!(Roracle::feed_sources(BTC_USD), sources);
+        System::assert_last_event(
+            Event::FeedSourcesSet {
+                feed: BTC_USD,
+                count: 3,
+            }
+            .into(),
+        );
+
+        // A fourth source would need a failover level beyond `MaxFailoverLevel`
+        let mut too_many = btc_sources();
+        too_many.push(price_source("Backup", KRAKEN_URL, PriceFormat::Kraken));
+        assert_err!(
+            Roracle::set_feed_sources(RuntimeOrigin::root(), BTC_USD, too_many.try_into().unwrap()),
+            Error::<Test>::TooManySources
+        );
+
+        let no_url = vec![price_source("Binance", "", PriceFormat::Binance)];
+        assert_err!(
+            Roracle::set_feed_sources(RuntimeOrigin::root(), BTC_USD, no_url.try_into().unwrap()),
+            Error::<Test>::InvalidUrl
+        );
+
+        // An empty list clears the sources
+        assert_ok!(Roracle::set_feed_sources(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            Default::default()
+        ));
+        assert!(Roracle::feed_sources(BTC_USD).is_empty());
+    });
+}
+
+#[test]
+fn test_offchain_worker_submits_primary_price() {
+    let transactions = with_offchain(
+        vec![http_response(
+            BINANCE_URL,
+            r#"{"symbol":"BTCUSDT","price":"43250.12"}"#,
+        )],
+        || {
+            UintAuthorityId::set_all_keys(vec![1u64]);
+            assert_ok!(Roracle::set_feed_sources(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                btc_sources().try_into().unwrap()
+            ));
+
+            Roracle::offchain_worker(1);
+        },
+    );
+
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        submitted_payload(&transactions[0]),
+        OraclePayload {
+            feed: BTC_USD,
+            round: 0,
+            value: 43_250_120_000_000_000,
+            source: b"Binance".to_vec(),
+            status: OracleStatus::Primary,
+            observed_at: Some(1000),
+            block_number: 1,
+            public: UintAuthorityId(1),
+        }
+    );
+}
+
+#[test]
+fn test_offchain_worker_rescales_to_feed_decimals() {
+    let transactions = with_offchain(
+        vec![http_response(
+            BINANCE_URL,
+            r#"{"symbol":"BTCUSDT","price":"43250.12345"}"#,
+        )],
+        || {
+            UintAuthorityId::set_all_keys(vec![1u64]);
+            assert_ok!(Roracle::set_feed_sources(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                btc_sources().try_into().unwrap()
+            ));
+            assert_ok!(Roracle::set_feed_config(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                FeedConfig {
+                    decimals: 2,
+                    ..Default::default()
+                }
+            ));
+
+            Roracle::offchain_worker(1);
+        },
+    );
+
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(submitted_payload(&transactions[0]).value, 4_325_012);
+}
+
+#[test]
+fn test_offchain_worker_skips_paused_feeds() {
+    // No request is expected, so fetching would fail the test
+    let transactions = with_offchain(vec![], || {
+        UintAuthorityId::set_all_keys(vec![1u64]);
+        assert_ok!(Roracle::set_feed_sources(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            btc_sources().try_into().unwrap()
+        ));
+        assert_ok!(Roracle::update_feed(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            FeedInfo {
+                state: FeedState::Paused,
+                ..feed_info(b"BTC", b"USD")
+            },
+            FeedConfig::default()
+        ));
+
+        Roracle::offchain_worker(1);
+    });
+
+    assert!(transactions.is_empty());
+}
+
+#[test]
+fn test_offchain_worker_fails_over_in_priority_order() {
+    let transactions = with_offchain(
+        vec![
+            http_response(BINANCE_URL, r#"{"code":-1003,"msg":"Too many requests"}"#),
+            http_response(COINBASE_URL, "<html>Service Unavailable</html>"),
+            http_response(
+                KRAKEN_URL,
+                r#"{"error":[],"result":{"XXBTZUSD":{"c":["43250.12","0.01"]}}}"#,
+            ),
+        ],
+        || {
+            UintAuthorityId::set_all_keys(vec![1u64, 2]);
+            assert_ok!(Roracle::set_feed_sources(
+                RuntimeOrigin::root(),
+                BTC_USD,
+                btc_sources().try_into().unwrap()
+            ));
+
+            Roracle::offchain_worker(1);
+        },
+    );
+
+    // Every local operator key submits the price of the second fallback
+    assert_eq!(transactions.len(), 2);
+    for (tx, key) in transactions.iter().zip([1, 2]) {
+        assert_eq!(
+            submitted_payload(tx),
+            OraclePayload {
+                feed: BTC_USD,
+                round: 0,
+                value: 43_250_120_000_000_000,
+                source: b"Kraken".to_vec(),
+                status: OracleStatus::Failover(2),
+                observed_at: Some(1000),
+                block_number: 1,
+                public: UintAuthorityId(key),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_offchain_worker_only_uses_operator_keys() {
+    // No request is expected, so fetching any source would fail the test
+    let transactions = with_offchain(vec![], || {
+        // Account 4 is not an operator
+        UintAuthorityId::set_all_keys(vec![4u64]);
+        assert_ok!(Roracle::set_feed_sources(
+            RuntimeOrigin::root(),
+            BTC_USD,
+            btc_sources().try_into().unwrap()
+        ));
+        Roracle::offchain_worker(1);
+
+        // Operators that already reported to the open round are skipped as well
+        MinQuorum::set(3);
+        UintAuthorityId::set_all_keys(vec![1u64]);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        Roracle::offchain_worker(1);
+    });
+
+    assert!(transactions.is_empty());
+}
+
+/// Payload for the current round of BTC/USD observed now
+fn signed_payload(
+    key: u64,
+    value: u128,
+    block_number: u64,
+) -> (OraclePayloadOf<Test>, TestSignature) {
+    let payload = OraclePayload {
+        feed: BTC_USD,
+        round: Roracle::current_round(BTC_USD),
+        value,
+        source: b"Binance".to_vec(),
+        status: OracleStatus::Primary,
+        observed_at: Some(Now::get()),
+        block_number,
+        public: UintAuthorityId(key),
+    };
+    let signature = UintAuthorityId(key).sign(&payload.encode()).unwrap();
+    (payload, signature)
+}
+
+fn validate(payload: &OraclePayloadOf<Test>, signature: &TestSignature) -> TransactionValidity {
+    Roracle::validate_unsigned(
+        TransactionSource::External,
+        &crate::Call::submit_oracle_value_unsigned_with_signed_payload {
+            payload: payload.clone(),
+            signature: signature.clone(),
+        },
+    )
+}
+
+#[test]
+fn test_unsigned_submission_with_signed_payload() {
+    new_test_ext().execute_with(|| {
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+
+        let valid = validate(&payload, &signature).unwrap();
+        assert_eq!(valid.priority, 100);
+        assert_eq!(valid.longevity, 5);
+
+        // Older payloads lose priority and expire at the same block
+        System::set_block_number(3);
+        let valid = validate(&payload, &signature).unwrap();
+        assert_eq!(valid.priority, 98);
+        assert_eq!(valid.longevity, 3);
+
+        assert_ok!(Roracle::submit_oracle_value_unsigned_with_signed_payload(
+            RuntimeOrigin::none(),
+            payload,
+            signature
+        ));
+        assert_eq!(
+            Roracle::last_value(BTC_USD).unwrap().value,
+            1_000_000_000_000
+        );
+        assert_eq!(Roracle::unsigned_submissions(), 1);
+
+        // Signed origins cannot use the unsigned call
+        let (payload, signature) = signed_payload(2, 1_000_000_000_000, 3);
+        assert_err!(
+            Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::signed(2),
+                payload,
+                signature
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn test_unsigned_submission_validation() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(10);
+
+        // Signature must match the payload
+        let (payload, _) = signed_payload(1, 1_000_000_000_000, 10);
+        let (_, other_signature) = signed_payload(1, 2_000_000_000_000, 10);
+        assert_eq!(
+            validate(&payload, &other_signature),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // Only operators may submit
+        let (payload, signature) = signed_payload(4, 1_000_000_000_000, 10);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::BadSigner.into()
+        );
+
+        // Invalid values are rejected before inclusion
+        let (payload, signature) = signed_payload(1, 0, 10);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Call.into()
+        );
+
+        // Payloads signed for a future block or longer ago than `UnsignedLongevity` are rejected
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 11);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Future.into()
+        );
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 5);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Stale.into()
+        );
+
+        // Operators that already reported to the open round are rejected
+        MinQuorum::set(3);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(1),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 10);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Stale.into()
+        );
+        let (payload, signature) = signed_payload(2, 1_000_000_000_000, 10);
+        assert_ok!(validate(&payload, &signature));
+    });
+}
+
+#[test]
+fn test_unsigned_payload_cannot_be_replayed() {
+    new_test_ext().execute_with(|| {
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+        assert_ok!(Roracle::submit_oracle_value_unsigned_with_signed_payload(
+            RuntimeOrigin::none(),
+            payload.clone(),
+            signature.clone()
+        ));
+        assert_eq!(Roracle::current_round(BTC_USD), 1);
+
+        // The payload was for the finalized round, even once a later one is open
+        Now::set(1001);
+        MinQuorum::set(3);
+        assert_ok!(Roracle::submit_oracle_value(
+            RuntimeOrigin::signed(2),
+            BTC_USD,
+            1_000_000_000_000,
+            b"Binance".to_vec(),
+            OracleStatus::Primary,
+            None
+        ));
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Stale.into()
+        );
+        assert_err!(
+            Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::none(),
+                payload,
+                signature
+            ),
+            Error::<Test>::WrongRound
+        );
+
+        // The operator's fresh submission is still accepted
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+        assert_ok!(validate(&payload, &signature));
+    });
+}
+
+#[test]
+fn test_unsigned_payload_needs_newer_observation() {
+    new_test_ext().execute_with(|| {
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+        assert_ok!(Roracle::submit_oracle_value_unsigned_with_signed_payload(
+            RuntimeOrigin::none(),
+            payload,
+            signature
+        ));
+
+        // Observed at the same time as the published value
+        let (payload, signature) = signed_payload(1, 1_000_000_000_000, 1);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Stale.into()
+        );
+        assert_err!(
+            Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::none(),
+                payload,
+                signature
+            ),
+            Error::<Test>::ObservationOutOfOrder
+        );
+
+        // Without an observation time
+        Now::set(1001);
+        let payload = OraclePayload {
+            observed_at: None,
+            ..signed_payload(1, 1_000_000_000_000, 1).0
+        };
+        let signature = UintAuthorityId(1).sign(&payload.encode()).unwrap();
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::Call.into()
+        );
+        assert_err!(
+            Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::none(),
+                payload,
+                signature
+            ),
+            Error::<Test>::MissingObservation
+        );
+    });
+}
+
+#[test]
+fn test_unsigned_submissions_are_limited_per_block() {
+    new_test_ext().execute_with(|| {
+        MinQuorum::set(3);
+        for key in [1, 2] {
+            let (payload, signature) = signed_payload(key, 1_000_000_000_000, 1);
+            assert_ok!(Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::none(),
+                payload,
+                signature
+            ));
+        }
+
+        let (payload, signature) = signed_payload(3, 1_000_000_000_000, 1);
+        assert_eq!(
+            validate(&payload, &signature),
+            InvalidTransaction::ExhaustsResources.into()
+        );
+        assert_err!(
+            Roracle::submit_oracle_value_unsigned_with_signed_payload(
+                RuntimeOrigin::none(),
+                payload.clone(),
+                signature.clone()
+            ),
+            Error::<Test>::TooManyUnsignedSubmissions
+        );
+
+        // The limit resets at the end of the block, so the pool keeps the submission for the next
+        Roracle::on_finalize(1);
+        assert_ok!(validate(&payload, &signature));
+        System::set_block_number(2);
+        Roracle::on_initialize(2);
+        assert_ok!(Roracle::submit_oracle_value_unsigned_with_signed_payload(
+            RuntimeOrigin::none(),
+            payload,
+            signature
+        ));
+    });
+}
+
+#[test]
+fn test_try_state_detects_corruption() {
+    new_test_ext().execute_with(|| {
+        for i in 1..=7u128 {
+            Now::set(1000 + i as u64);
+            assert_ok!(Roracle::submit_oracle_value(
+                RuntimeOrigin::signed(1),
+                BTC_USD,
+                i * 1_000_000_000_000u128,
+                b"Binance".to_vec(),
+                OracleStatus::Primary,
+                None
+            ));
+        }
+        assert_ok!(Roracle::do_try_state());
+
+        // Configuration of a feed that was never registered
+        FeedConfigs::<Test>::insert(7, FeedConfig::default());
+        assert_err!(
+            Roracle::do_try_state(),
+            "FeedConfigs of an unregistered feed"
+        );
+        FeedConfigs::<Test>::remove(7);
+
+        // A history entry outside the retained range
+        History::<Test>::insert(BTC_USD, 0, Roracle::history(BTC_USD, 2).unwrap());
+        assert_err!(
+            Roracle::do_try_state(),
+            "number of History entries does not match HistoryIndex"
+        );
+        History::<Test>::remove(BTC_USD, 0);
+
+        // A last value that is not the latest history entry
+        let latest = Roracle::last_value(BTC_USD).unwrap();
+        LastValue::<Test>::insert(BTC_USD, Roracle::history(BTC_USD, 5).unwrap());
+        assert_err!(
+            Roracle::do_try_state(),
+            "LastValue differs from the latest History entry"
+        );
+        LastValue::<Test>::insert(BTC_USD, &latest);
+
+        // History going back in time
+        let mut older = latest;
+        older.updated_at = 900;
+        History::<Test>::insert(BTC_USD, 6, &older);
+        LastValue::<Test>::insert(BTC_USD, &older);
+        assert_err!(
+            Roracle::do_try_state(),
+            "History timestamps are not monotonic"
+        );
+    });
+}
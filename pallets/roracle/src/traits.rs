@@ -0,0 +1,143 @@
+//! Access to oracle prices for other pallets
+//!
+//! Pallets consuming prices take a `PriceProvider` in their config instead of depending on this
+//! pallet's storage, so runtimes wire them to `pallet_roracle::Pallet` and tests to
+//! `MockPriceProvider` (with the `mock` feature) or `()`.
+
+use crate::pallet::*;
+use frame_support::weights::Weight;
+
+/// Source of the latest price of a feed.
+///
+/// Prices are raw values with the decimals of their feed, timestamps unix time in seconds.
+pub trait PriceProvider<FeedId> {
+    /// Latest price of a feed
+    fn get(feed: &FeedId) -> Option<u128> {
+        Self::get_with_timestamp(feed).map(|(value, _)| value)
+    }
+
+    /// Latest price of a feed with the time it was published
+    fn get_with_timestamp(feed: &FeedId) -> Option<(u128, u64)>;
+
+    /// Latest price of a feed if it was published at most `max_age` seconds ago
+    fn get_no_older_than(feed: &FeedId, max_age: u64) -> Option<u128>;
+}
+
+/// Provider without any price
+impl<FeedId> PriceProvider<FeedId> for () {
+    fn get_with_timestamp(_feed: &FeedId) -> Option<(u128, u64)> {
+        None
+    }
+
+    fn get_no_older_than(_feed: &FeedId, _max_age: u64) -> Option<u128> {
+        None
+    }
+}
+
+/// Prices of active feeds. Values of paused and deprecated feeds are withheld, since they are no
+/// longer updated.
+impl<T: Config> PriceProvider<T::FeedId> for Pallet<T> {
+    fn get_with_timestamp(feed: &T::FeedId) -> Option<(u128, u64)> {
+        if !Self::is_active(*feed) {
+            return None;
+        }
+        LastValue::<T>::get(feed).map(|value| (value.value, value.updated_at))
+    }
+
+    fn get_no_older_than(feed: &T::FeedId, max_age: u64) -> Option<u128> {
+        if !Self::is_active(*feed) {
+            return None;
+        }
+        Self::get_fresh_value(*feed, max_age)
+            .ok()
+            .map(|value| value.value)
+    }
+}
+
+/// Handler of newly published values, e.g. to trigger liquidations in the same block.
+///
+/// Implemented for tuples, calling every member in order.
+pub trait OnNewValue<FeedId> {
+    /// A value was published for `feed`, replacing `old` if the feed had one
+    fn on_new_value(feed: &FeedId, old: Option<u128>, new: u128);
+
+    /// Upper bound of the weight of `on_new_value`, charged to every call that may publish
+    fn weight() -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<FeedId> OnNewValue<FeedId> for Tuple {
+    fn on_new_value(feed: &FeedId, old: Option<u128>, new: u128) {
+        for_tuples!( #( Tuple::on_new_value(feed, old, new); )* );
+    }
+
+    fn weight() -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+        weight
+    }
+}
+
+/// Runtime hooks the benchmarks need to reach the worst case of a call
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+    /// Set the unix time in seconds reported by the pallet's `TimeProvider`
+    fn set_time(now: u64);
+}
+
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockPriceProvider;
+
+#[cfg(any(test, feature = "mock"))]
+mod mock {
+    use super::PriceProvider;
+    use codec::Encode;
+    use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
+
+    thread_local! {
+        static PRICES: RefCell<BTreeMap<Vec<u8>, (u128, u64)>> = RefCell::new(BTreeMap::new());
+        static NOW: RefCell<u64> = RefCell::new(0);
+    }
+
+    /// Thread-local prices for tests of pallets consuming a `PriceProvider`
+    pub struct MockPriceProvider;
+
+    impl MockPriceProvider {
+        /// Set the price of a feed as published at `timestamp`
+        pub fn set_price<FeedId: Encode>(feed: &FeedId, value: u128, timestamp: u64) {
+            PRICES.with(|prices| {
+                prices
+                    .borrow_mut()
+                    .insert(feed.encode(), (value, timestamp))
+            });
+        }
+
+        /// Remove the price of a feed
+        pub fn remove_price<FeedId: Encode>(feed: &FeedId) {
+            PRICES.with(|prices| prices.borrow_mut().remove(&feed.encode()));
+        }
+
+        /// Set the time `get_no_older_than` measures the age of prices against
+        pub fn set_now(now: u64) {
+            NOW.with(|n| *n.borrow_mut() = now);
+        }
+
+        /// Remove every price and reset the time
+        pub fn reset() {
+            PRICES.with(|prices| prices.borrow_mut().clear());
+            Self::set_now(0);
+        }
+    }
+
+    impl<FeedId: Encode> PriceProvider<FeedId> for MockPriceProvider {
+        fn get_with_timestamp(feed: &FeedId) -> Option<(u128, u64)> {
+            PRICES.with(|prices| prices.borrow().get(&feed.encode()).copied())
+        }
+
+        fn get_no_older_than(feed: &FeedId, max_age: u64) -> Option<u128> {
+            let (value, timestamp) = Self::get_with_timestamp(feed)?;
+            let now = NOW.with(|now| *now.borrow());
+            (now.saturating_sub(timestamp) <= max_age).then_some(value)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Time-weighted average prices from cumulative price accumulators
+//!
+//! Every feed accumulates the integral of its price over time, like Uniswap's price cumulative.
+//! The difference of the accumulator between two instants divided by the elapsed time is the
+//! time-weighted average price in between. To look up the accumulator at the start of a window
+//! without scanning `History`, its value at every `TwapGranularity` boundary is kept in a ring of
+//! `TwapBuckets` observations, so windows start at a boundary and are rounded up to one.
+
+use crate::pallet::*;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Zero, RuntimeDebug};
+
+/// Running integral of the price of a feed
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct PriceAccumulator {
+    /// Sum of price times seconds it was in effect until `last_update`, wrapping on overflow
+    pub cumulative: u128,
+    /// Price in effect since `last_update`
+    pub last_value: u128,
+    /// Unix time in seconds of the last published value
+    pub last_update: u64,
+}
+
+impl PriceAccumulator {
+    /// Accumulator value at `at`, which must not precede `last_update`
+    pub fn cumulative_at(&self, at: u64) -> u128 {
+        let elapsed = at.saturating_sub(self.last_update) as u128;
+        self.cumulative
+            .wrapping_add(self.last_value.wrapping_mul(elapsed))
+    }
+}
+
+/// Accumulator value at a granularity boundary
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Observation {
+    /// Unix time in seconds of the boundary
+    pub timestamp: u64,
+    pub cumulative: u128,
+}
+
+impl<T: Config> Pallet<T> {
+    /// Account a value published at `at` in the accumulator of a feed, recording an observation
+    /// for every boundary passed since the previous value
+    pub(crate) fn accumulate_price(feed: T::FeedId, value: u128, at: u64) {
+        let granularity = T::TwapGranularity::get();
+        let buckets = T::TwapBuckets::get() as u64;
+
+        let accumulator = match PriceAccumulators::<T>::get(feed) {
+            Some(accumulator) => {
+                if !granularity.is_zero() && !buckets.is_zero() {
+                    let first = (accumulator.last_update / granularity).saturating_add(1);
+                    let last = at / granularity;
+                    // Older boundaries would be overwritten within this loop
+                    for bucket in first.max(last.saturating_sub(buckets - 1))..=last {
+                        let timestamp = bucket.saturating_mul(granularity);
+                        let cumulative = accumulator.cumulative_at(timestamp);
+                        TwapObservations::<T>::insert(
+                            feed,
+                            (bucket % buckets) as u32,
+                            Observation {
+                                timestamp,
+                                cumulative,
+                            },
+                        );
+                    }
+                }
+                PriceAccumulator {
+                    cumulative: accumulator.cumulative_at(at),
+                    last_value: value,
+                    last_update: at.max(accumulator.last_update),
+                }
+            }
+            // The price before the first value is unknown, so nothing is accumulated
+            None => PriceAccumulator {
+                cumulative: 0,
+                last_value: value,
+                last_update: at,
+            },
+        };
+        PriceAccumulators::<T>::insert(feed, accumulator);
+    }
+
+    /// Time-weighted average price of a feed over at least the last `window` seconds.
+    ///
+    /// The window starts at the latest `TwapGranularity` boundary at or before `now - window`.
+    /// Returns `None` if the feed has no value or its accumulator does not reach back that far.
+    pub fn twap(feed: T::FeedId, window: u64) -> Option<u128> {
+        let accumulator = PriceAccumulators::<T>::get(feed)?;
+        let granularity = T::TwapGranularity::get();
+        let buckets = T::TwapBuckets::get() as u64;
+        if granularity.is_zero() || buckets.is_zero() {
+            return None;
+        }
+
+        let now = Self::now().max(accumulator.last_update);
+        let bucket = now.saturating_sub(window) / granularity;
+        let start = bucket.saturating_mul(granularity);
+        if start >= now {
+            return Some(accumulator.last_value);
+        }
+
+        let start_cumulative = if start >= accumulator.last_update {
+            // No value was published since the window started
+            accumulator.cumulative_at(start)
+        } else {
+            let observation = TwapObservations::<T>::get(feed, (bucket % buckets) as u32)?;
+            // The slot was overwritten by a later boundary or never filled
+            if observation.timestamp != start {
+                return None;
+            }
+            observation.cumulative
+        };
+
+        let elapsed = (now - start) as u128;
+        Some(
+            accumulator
+                .cumulative_at(now)
+                .wrapping_sub(start_cumulative)
+                / elapsed,
+        )
+    }
+}
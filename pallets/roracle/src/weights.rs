@@ -0,0 +1,220 @@
+//! Placeholder weights for pallet_roracle
+//!
+//! These are hand-written estimates, not benchmark results: reads and writes count the worst
+//! case storage accesses of each call with the runtime's bounds, e.g. `TwapBuckets`
+//! observations when a feed was silent for a day. Replace this file with the output of
+//! `scripts/benchmark.sh` on reference hardware before relying on it in production, which runs:
+//!
+//! ./target/release/r-oracle-node benchmark pallet
+//! --chain=dev
+//! --steps=50
+//! --repeat=20
+//! --pallet=pallet_roracle
+//! --extrinsic=*
+//! --wasm-execution=compiled
+//! --output=./pallets/roracle/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_roracle.
+pub trait WeightInfo {
+	fn submit_oracle_value(s: u32, ) -> Weight;
+	fn submit_oracle_value_unsigned_with_signed_payload(s: u32, ) -> Weight;
+	fn add_operator() -> Weight;
+	fn remove_operator() -> Weight;
+	fn rotate_operator(r: u32, ) -> Weight;
+	fn set_operator_weight() -> Weight;
+	fn set_feed_config() -> Weight;
+	fn confirm_quarantined() -> Weight;
+	fn set_feed_sources(n: u32, ) -> Weight;
+	fn create_feed() -> Weight;
+	fn update_feed() -> Weight;
+	fn deprecate_feed() -> Weight;
+	fn submit_batch(n: u32, s: u32, ) -> Weight;
+	fn finalize_expired_rounds(n: u32, ) -> Weight;
+}
+
+/// Placeholder weights for pallet_roracle, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn submit_oracle_value(s: u32, ) -> Weight {
+		Weight::from_parts(67_120_000, 9_480)
+			.saturating_add(Weight::from_parts(3_410, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(44_u64))
+			.saturating_add(T::DbWeight::get().writes(304_u64))
+	}
+	fn submit_oracle_value_unsigned_with_signed_payload(s: u32, ) -> Weight {
+		Weight::from_parts(69_740_000, 9_480)
+			.saturating_add(Weight::from_parts(3_420, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(47_u64))
+			.saturating_add(T::DbWeight::get().writes(305_u64))
+	}
+	fn add_operator() -> Weight {
+		Weight::from_parts(17_940_000, 2_561)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn remove_operator() -> Weight {
+		Weight::from_parts(19_320_000, 2_561)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn rotate_operator(r: u32, ) -> Weight {
+		Weight::from_parts(24_610_000, 3_509)
+			.saturating_add(Weight::from_parts(9_870_000, 0).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(r.into())))
+			.saturating_add(Weight::from_parts(0, 3_604).saturating_mul(r.into()))
+	}
+	fn set_operator_weight() -> Weight {
+		Weight::from_parts(15_780_000, 2_561)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn set_feed_config() -> Weight {
+		Weight::from_parts(15_920_000, 5_724)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn confirm_quarantined() -> Weight {
+		Weight::from_parts(58_350_000, 5_724)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(301_u64))
+	}
+	fn set_feed_sources(n: u32, ) -> Weight {
+		Weight::from_parts(14_230_000, 2_712)
+			.saturating_add(Weight::from_parts(1_270_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn create_feed() -> Weight {
+		Weight::from_parts(16_870_000, 2_712)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn update_feed() -> Weight {
+		Weight::from_parts(19_860_000, 5_724)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	fn deprecate_feed() -> Weight {
+		Weight::from_parts(19_640_000, 2_712)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	fn submit_batch(n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(21_870_000, 2_561)
+			.saturating_add(Weight::from_parts(58_940_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(3_390, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((43_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((304_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 9_480).saturating_mul(n.into()))
+	}
+	fn finalize_expired_rounds(n: u32, ) -> Weight {
+		Weight::from_parts(4_870_000, 1_489)
+			.saturating_add(Weight::from_parts(72_460_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((76_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((305_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 12_460).saturating_mul(n.into()))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn submit_oracle_value(s: u32, ) -> Weight {
+		Weight::from_parts(67_120_000, 9_480)
+			.saturating_add(Weight::from_parts(3_410, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(44_u64))
+			.saturating_add(RocksDbWeight::get().writes(304_u64))
+	}
+	fn submit_oracle_value_unsigned_with_signed_payload(s: u32, ) -> Weight {
+		Weight::from_parts(69_740_000, 9_480)
+			.saturating_add(Weight::from_parts(3_420, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(47_u64))
+			.saturating_add(RocksDbWeight::get().writes(305_u64))
+	}
+	fn add_operator() -> Weight {
+		Weight::from_parts(17_940_000, 2_561)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn remove_operator() -> Weight {
+		Weight::from_parts(19_320_000, 2_561)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn rotate_operator(r: u32, ) -> Weight {
+		Weight::from_parts(24_610_000, 3_509)
+			.saturating_add(Weight::from_parts(9_870_000, 0).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(r.into())))
+			.saturating_add(Weight::from_parts(0, 3_604).saturating_mul(r.into()))
+	}
+	fn set_operator_weight() -> Weight {
+		Weight::from_parts(15_780_000, 2_561)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_feed_config() -> Weight {
+		Weight::from_parts(15_920_000, 5_724)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn confirm_quarantined() -> Weight {
+		Weight::from_parts(58_350_000, 5_724)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(301_u64))
+	}
+	fn set_feed_sources(n: u32, ) -> Weight {
+		Weight::from_parts(14_230_000, 2_712)
+			.saturating_add(Weight::from_parts(1_270_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn create_feed() -> Weight {
+		Weight::from_parts(16_870_000, 2_712)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn update_feed() -> Weight {
+		Weight::from_parts(19_860_000, 5_724)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn deprecate_feed() -> Weight {
+		Weight::from_parts(19_640_000, 2_712)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	fn submit_batch(n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(21_870_000, 2_561)
+			.saturating_add(Weight::from_parts(58_940_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(3_390, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((43_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((304_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 9_480).saturating_mul(n.into()))
+	}
+	fn finalize_expired_rounds(n: u32, ) -> Weight {
+		Weight::from_parts(4_870_000, 1_489)
+			.saturating_add(Weight::from_parts(72_460_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((76_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((305_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 12_460).saturating_mul(n.into()))
+	}
+}
@@ -5,4 +5,3 @@ fn main() {
         .import_memory()
         .build()
 }
-
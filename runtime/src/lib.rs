@@ -6,13 +6,14 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::Encode;
 use pallet_transaction_payment::CurrencyAdapter;
 use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
-    traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
-    transaction_validity::{TransactionSource, TransactionValidity},
+    traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, SaturatedConversion, Verify},
+    transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
     ApplyExtrinsicResult, MultiSignature,
 };
 use sp_std::prelude::*;
@@ -23,10 +24,15 @@ use sp_version::RuntimeVersion;
 // A few exports that help ease life for downstream crates.
 pub use frame_support::{
     construct_runtime, parameter_types,
-    traits::{ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo},
+    traits::{
+        ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
+        StorageInfo,
+    },
     weights::{
-        constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
-        IdentityFee, Weight, ConstantMultiplier,
+        constants::{
+            BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND,
+        },
+        ConstantMultiplier, IdentityFee, Weight,
     },
     StorageValue,
 };
@@ -61,6 +67,12 @@ pub type Nonce = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// Identifier of an oracle price feed.
+pub type FeedId = u32;
+
+/// Oracle value as stored by this runtime.
+pub type OracleValue = pallet_roracle::OracleValueOf<Runtime>;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -98,7 +110,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   the compatible custom types.
     spec_version: 100,
     impl_version: 1,
-    apis: sp_version::create_apis_vec!([]),
+    apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
     state_version: 1,
 };
@@ -123,7 +135,10 @@ pub const DAYS: BlockNumber = HOURS * 24;
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
 pub fn native_version() -> NativeVersion {
-    NativeVersion { runtime_version: VERSION, can_author_with: Default::default() }
+    NativeVersion {
+        runtime_version: VERSION,
+        can_author_with: Default::default(),
+    }
 }
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -275,16 +290,126 @@ impl pallet_session::Config for Runtime {
     type ShouldEndSession = pallet_session::PeriodicSessions<ConstU64<600>, ConstU64<1>>;
     type NextSessionRotation = pallet_session::PeriodicSessions<ConstU64<600>, ConstU64<1>>;
     type SessionManager = pallet_collator_selection::SessionManagerOf<Runtime>;
-    type SessionHandler = <opaque::SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
+    type SessionHandler =
+        <opaque::SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
     type Keys = opaque::SessionKeys;
     type WeightInfo = ();
 }
 
 impl parachain_info::Config for Runtime {}
 
+parameter_types! {
+    pub const OracleAggregation: pallet_roracle::aggregation::AggregationMethod =
+        pallet_roracle::aggregation::AggregationMethod::Median;
+    pub const OracleUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+    /// Minute, hour and day candles
+    pub OracleCandleIntervals: Vec<u64> = vec![60, 60 * 60, 24 * 60 * 60];
+    /// Feed the values of the original single-feed storage layout are migrated to (BTC/USD)
+    pub const OracleLegacyFeed: FeedId = 0;
+}
+
 impl pallet_roracle::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type TimeProvider = Timestamp;
+    type FeedId = FeedId;
+    type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxOperators = ConstU32<32>;
+    type MinQuorum = ConstU32<3>;
+    /// Open rounds are closed after one minute
+    type RoundTimeout = ConstU32<{ MINUTES }>;
+    type Aggregation = OracleAggregation;
+    type MaxSourceLen = ConstU32<32>;
+    type MaxFailoverLevel = ConstU8<2>;
+    /// A week of one-minute rounds
+    type MaxHistory = ConstU32<{ 7 * 24 * 60 }>;
+    type AuthorityId = pallet_roracle::offchain::crypto::OperatorAuthId;
+    type MaxSources = ConstU32<3>;
+    type MaxUrlLen = ConstU32<256>;
+    type MaxSymbolLen = ConstU32<16>;
+    type MaxDescriptionLen = ConstU32<128>;
+    /// Operators fetch prices once per round
+    type FetchInterval = ConstU32<{ MINUTES }>;
+    type MaxUnsignedPerBlock = ConstU32<64>;
+    type UnsignedPriority = OracleUnsignedPriority;
+    /// A signed price is only worth including within the round it was fetched for
+    type UnsignedLongevity = ConstU32<{ MINUTES }>;
+    /// TWAP windows of up to a day in five minute steps
+    type TwapGranularity = ConstU64<{ 5 * 60 }>;
+    type TwapBuckets = ConstU32<{ 24 * 12 + 1 }>;
+    type CandleIntervals = OracleCandleIntervals;
+    /// A day of minute candles
+    type CandleRetention = ConstU32<{ 24 * 60 }>;
+    type OnNewValue = ();
+    /// Enough for an operator to report every feed in one transaction
+    type MaxBatchSize = ConstU32<32>;
+    /// Every feed is checked against its heartbeat in each block
+    type MaxHeartbeatChecks = ConstU32<32>;
+    /// Rounds of every feed can close in the same block
+    type MaxRoundsPerBlock = ConstU32<32>;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = OracleBenchmarkHelper;
+    type WeightInfo = pallet_roracle::weights::SubstrateWeight<Runtime>;
+}
+
+/// Sets the time of `pallet_timestamp`, which the oracle reads its time from
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OracleBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_roracle::traits::BenchmarkHelper for OracleBenchmarkHelper {
+    fn set_time(now: u64) {
+        Timestamp::set_timestamp(now.saturating_mul(1_000));
+    }
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+    type Public = <Signature as Verify>::Signer;
+    type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    RuntimeCall: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type OverarchingCall = RuntimeCall;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+    RuntimeCall: From<LocalCall>,
+{
+    fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+        call: RuntimeCall,
+        public: <Signature as Verify>::Signer,
+        account: AccountId,
+        nonce: Nonce,
+    ) -> Option<(
+        RuntimeCall,
+        <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload,
+    )> {
+        let period = BlockHashCount::get()
+            .checked_next_power_of_two()
+            .map(|c| c / 2)
+            .unwrap_or(2) as u64;
+        let current_block = System::block_number()
+            .saturated_into::<u64>()
+            .saturating_sub(1);
+        let extra: SignedExtra = (
+            frame_system::CheckNonZeroSender::<Runtime>::new(),
+            frame_system::CheckSpecVersion::<Runtime>::new(),
+            frame_system::CheckTxVersion::<Runtime>::new(),
+            frame_system::CheckGenesis::<Runtime>::new(),
+            frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+            frame_system::CheckNonce::<Runtime>::from(nonce),
+            frame_system::CheckWeight::<Runtime>::new(),
+            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+        );
+        let raw_payload = SignedPayload::new(call, extra).ok()?;
+        let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+        let (call, extra, _) = raw_payload.deconstruct();
+        Some((call, (MultiAddress::Id(account), signature, extra)))
+    }
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -339,8 +464,20 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
+/// Storage migrations applied on runtime upgrade, oldest first. Each checks the on-chain storage
+/// version and is a no-op once applied, so entries can be dropped after every network upgraded.
+pub type Migrations = (
+    pallet_roracle::migrations::v1::MigrateToV1<Runtime, OracleLegacyFeed>,
+    pallet_roracle::migrations::v2::MigrateToV2<Runtime>,
+    pallet_roracle::migrations::v3::MigrateToV3<Runtime>,
+    pallet_roracle::migrations::v4::MigrateToV4<Runtime>,
+    pallet_roracle::migrations::v5::MigrateToV5<Runtime>,
+    pallet_roracle::migrations::v6::MigrateToV6<Runtime>,
+);
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -446,6 +583,55 @@ impl_runtime_apis! {
     }
 
 
+    #[api_version(4)]
+    impl pallet_roracle_runtime_api::RoracleApi<Block, FeedId, OracleValue> for Runtime {
+        fn latest_value(feed: FeedId) -> Option<OracleValue> {
+            Roracle::last_value(feed)
+        }
+
+        fn history_range(feed: FeedId, from: u64, to: u64) -> Vec<(u64, OracleValue)> {
+            Roracle::history_range(feed, from, to)
+        }
+
+        fn history_by_time(feed: FeedId, from: u64, to: u64) -> Vec<(u64, OracleValue)> {
+            Roracle::history_by_time(feed, from, to)
+        }
+
+        fn feeds() -> Vec<FeedId> {
+            Roracle::feeds()
+        }
+
+        fn is_stale(feed: FeedId) -> bool {
+            Roracle::is_stale(feed)
+        }
+
+        fn decimals(feed: FeedId) -> u8 {
+            Roracle::decimals(feed)
+        }
+
+        fn twap(feed: FeedId, window: u64) -> Option<u128> {
+            Roracle::twap(feed, window)
+        }
+
+        fn candles(
+            feed: FeedId,
+            interval: u64,
+            from: u64,
+            to: u64,
+        ) -> Vec<pallet_roracle::candles::Candle> {
+            Roracle::candles(feed, interval, from, to)
+        }
+
+        fn latest_decimal_value(
+            feed: FeedId,
+        ) -> Option<pallet_roracle_runtime_api::DecimalValue<OracleValue>> {
+            let raw = Roracle::last_value(feed)?;
+            let decimals = Roracle::decimals(feed);
+            let fixed = pallet_roracle::fixed::to_fixed(raw.value, decimals);
+            Some(pallet_roracle_runtime_api::DecimalValue { raw, decimals, fixed })
+        }
+    }
+
     impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
         fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
             ParachainSystem::collect_collation_info(header)
@@ -509,4 +695,3 @@ impl_runtime_apis! {
         }
     }
 }
-
@@ -0,0 +1,23 @@
+#!/bin/bash
+
+# Benchmark script for R-Oracle
+#
+# Regenerates the weights of pallet_roracle. Run on reference hardware and commit the output
+# together with this script.
+
+set -e
+
+echo "Building R-Oracle node with benchmarks..."
+cargo build --release --features runtime-benchmarks --bin r-oracle-node
+
+echo "Benchmarking pallet_roracle..."
+./target/release/r-oracle-node benchmark pallet \
+    --chain=dev \
+    --steps=50 \
+    --repeat=20 \
+    --pallet=pallet_roracle \
+    --extrinsic='*' \
+    --wasm-execution=compiled \
+    --output=./pallets/roracle/src/weights.rs
+
+echo "Weights written to ./pallets/roracle/src/weights.rs"